@@ -0,0 +1,5 @@
+// https://doc.rust-lang.org/reference/items/modules.html#module-source-filenames
+// Preferred way is to name modules with their subfolder name now (no longer mod.rs)
+pub mod db;
+pub mod saved_encounter;
+pub mod writer;
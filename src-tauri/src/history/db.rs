@@ -0,0 +1,581 @@
+use crate::history::saved_encounter::{SAVED_ENCOUNTER_VERSION, SavedEncounter};
+use crate::live::opcodes_models::class::{self, Class, ClassSpec};
+use blueprotobuf_lib::blueprotobuf::EEntityType;
+use log::{info, warn};
+use rusqlite::types::ToSql;
+use rusqlite::{Connection, OptionalExtension, Transaction, params, params_from_iter};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+pub type HistoryDbMutex = Arc<Mutex<HistoryDb>>;
+
+const DB_FILE_NAME: &str = "history.db";
+
+// Schema migrations, MIGRATIONS[i] upgrades the database from user_version i to i + 1.
+// Never edit an entry once released, append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE encounters (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        data_version INTEGER NOT NULL,
+        time_fight_start_ms INTEGER NOT NULL,
+        time_last_combat_packet_ms INTEGER NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        end_reason TEXT NOT NULL,
+        total_dmg INTEGER NOT NULL,
+        total_heal INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX idx_encounters_time_fight_start_ms ON encounters (time_fight_start_ms);
+",
+    "
+    ALTER TABLE encounters ADD COLUMN map_id INTEGER;
+    ALTER TABLE encounters ADD COLUMN line_id INTEGER;
+    ALTER TABLE encounters ADD COLUMN local_player_uid INTEGER;
+    ALTER TABLE encounters ADD COLUMN local_player_name TEXT;
+    CREATE TABLE encounter_players (
+        encounter_id INTEGER NOT NULL REFERENCES encounters (id) ON DELETE CASCADE,
+        player_uid INTEGER NOT NULL,
+        name TEXT,
+        class_name TEXT NOT NULL,
+        class_spec_name TEXT NOT NULL,
+        total_dmg INTEGER NOT NULL,
+        total_heal INTEGER NOT NULL,
+        PRIMARY KEY (encounter_id, player_uid)
+    );
+    CREATE INDEX idx_encounter_players_class ON encounter_players (class_name, class_spec_name);
+    CREATE TABLE encounter_monsters (
+        encounter_id INTEGER NOT NULL REFERENCES encounters (id) ON DELETE CASCADE,
+        monster_id INTEGER NOT NULL,
+        PRIMARY KEY (encounter_id, monster_id)
+    );
+    CREATE INDEX idx_encounter_monsters_monster_id ON encounter_monsters (monster_id);
+",
+    "
+    ALTER TABLE encounters ADD COLUMN level_map_id INTEGER;
+    ALTER TABLE encounters ADD COLUMN dungeon_guid TEXT;
+    CREATE INDEX idx_encounters_dungeon_guid ON encounters (dungeon_guid);
+",
+];
+// First schema version with the searchable columns filled by `index_encounter`
+const INDEXED_SCHEMA_VERSION: usize = 2;
+
+pub const DEFAULT_HISTORY_PAGE_SIZE: u32 = 20;
+pub const MAX_HISTORY_PAGE_SIZE: u32 = 200;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum HistorySortBy {
+    #[default]
+    Date,
+    Duration,
+    TotalDmg,
+    TotalDps,
+}
+
+/// Filters, sort and page for listing saved encounters, every filter is optional
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+    pub monster_id: Option<i32>,
+    pub map_id: Option<u32>,
+    pub line_id: Option<u32>,
+    pub level_map_id: Option<u32>,
+    pub dungeon_guid: Option<String>, // every encounter of one dungeon run
+    pub from_ms: Option<f64>,
+    pub to_ms: Option<f64>,
+    pub local_player_uid: Option<String>,
+    pub local_player_name: Option<String>,
+    pub class_name: Option<String>,      // e.g. "Frost Mage"
+    pub class_spec_name: Option<String>, // e.g. "Icicle"
+    pub min_duration_ms: Option<u32>,
+    pub sort_by: Option<HistorySortBy>,
+    pub sort_ascending: Option<bool>, // newest/biggest first by default
+    pub page: Option<u32>,            // 0-based
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct HistoryEncounterSummary {
+    pub id: i64,
+    pub time_fight_start_ms: i64,
+    pub time_last_combat_packet_ms: i64,
+    pub duration_ms: i64,
+    pub end_reason: String,
+    pub total_dmg: i64,
+    pub total_heal: i64,
+    pub map_id: Option<u32>,
+    pub line_id: Option<u32>,
+    pub level_map_id: Option<u32>,
+    pub dungeon_guid: Option<String>,
+    pub local_player_name: Option<String>,
+    pub monster_ids: Vec<i32>,
+    pub player_count: i64,
+    pub top_player_name: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct HistoryPage {
+    pub summaries: Vec<HistoryEncounterSummary>,
+    pub total_count: i64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+/// SQLite store for completed encounters, the full encounter is kept as a versioned JSON blob
+#[derive(Debug)]
+pub struct HistoryDb {
+    conn: Connection,
+}
+
+impl HistoryDb {
+    pub fn open(dir: &Path) -> rusqlite::Result<Self> {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            warn!("Failed to create history dir {}: {e}", dir.display());
+        }
+        let path = dir.join(DB_FILE_NAME);
+        info!("Opening encounter history at {}", path.display());
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    /// Falls back to an in-memory database so the app keeps working if the file can't be opened
+    pub fn open_or_in_memory(dir: &Path) -> Self {
+        Self::open(dir).unwrap_or_else(|e| {
+            warn!("Failed to open encounter history, history won't be saved to disk: {e}");
+            Self::open_in_memory().expect("failed to open in-memory history db")
+        })
+    }
+
+    fn init(conn: Connection) -> rusqlite::Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        let mut history_db = Self { conn };
+        history_db.migrate()?;
+        Ok(history_db)
+    }
+
+    fn migrate(&mut self) -> rusqlite::Result<()> {
+        let user_version: usize = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if user_version > MIGRATIONS.len() {
+            warn!(
+                "History db schema version {user_version} is newer than this version of the app ({})",
+                MIGRATIONS.len()
+            );
+            return Ok(());
+        }
+
+        let tx = self.conn.transaction()?;
+        for (version, migration) in MIGRATIONS.iter().enumerate().skip(user_version) {
+            info!("Migrating history db to schema version {}", version + 1);
+            tx.execute_batch(migration)?;
+        }
+        if user_version < INDEXED_SCHEMA_VERSION {
+            reindex_encounters(&tx)?;
+        }
+        tx.pragma_update(None, "user_version", MIGRATIONS.len())?;
+        tx.commit()
+    }
+
+    pub fn save_encounter(&mut self, saved_encounter: &SavedEncounter) -> rusqlite::Result<i64> {
+        let data = serde_json::to_string(saved_encounter)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let tx = self.conn.transaction()?;
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_possible_wrap)]
+        tx.execute(
+            "INSERT INTO encounters (data_version, time_fight_start_ms, time_last_combat_packet_ms, duration_ms, end_reason, total_dmg, total_heal, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                SAVED_ENCOUNTER_VERSION,
+                saved_encounter.time_fight_start_ms as i64,
+                saved_encounter.time_last_combat_packet_ms as i64,
+                (saved_encounter.time_last_combat_packet_ms - saved_encounter.time_fight_start_ms)
+                    as i64,
+                saved_encounter.end_reason,
+                saved_encounter.dmg_stats.value,
+                saved_encounter.heal_stats.value,
+                data,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        index_encounter(&tx, id, saved_encounter)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn query_encounters(&self, query: &HistoryQuery) -> rusqlite::Result<HistoryPage> {
+        let mut conditions: Vec<&str> = Vec::new();
+        let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+        if let Some(monster_id) = query.monster_id {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM encounter_monsters m WHERE m.encounter_id = e.id AND m.monster_id = ?)",
+            );
+            values.push(Box::new(monster_id));
+        }
+        if let Some(map_id) = query.map_id {
+            conditions.push("e.map_id = ?");
+            values.push(Box::new(map_id));
+        }
+        if let Some(line_id) = query.line_id {
+            conditions.push("e.line_id = ?");
+            values.push(Box::new(line_id));
+        }
+        if let Some(level_map_id) = query.level_map_id {
+            conditions.push("e.level_map_id = ?");
+            values.push(Box::new(level_map_id));
+        }
+        if let Some(dungeon_guid) = &query.dungeon_guid {
+            conditions.push("e.dungeon_guid = ?");
+            values.push(Box::new(dungeon_guid.clone()));
+        }
+        if let Some(from_ms) = query.from_ms {
+            conditions.push("e.time_fight_start_ms >= ?");
+            values.push(Box::new(from_ms as i64));
+        }
+        if let Some(to_ms) = query.to_ms {
+            conditions.push("e.time_fight_start_ms <= ?");
+            values.push(Box::new(to_ms as i64));
+        }
+        if let Some(local_player_uid) = &query.local_player_uid {
+            let local_player_uid: i64 = local_player_uid.parse().map_err(|_| {
+                rusqlite::Error::InvalidParameterName(format!(
+                    "Invalid local player uid {local_player_uid}"
+                ))
+            })?;
+            conditions.push("e.local_player_uid = ?");
+            values.push(Box::new(local_player_uid));
+        }
+        if let Some(local_player_name) = &query.local_player_name {
+            conditions.push("e.local_player_name = ? COLLATE NOCASE");
+            values.push(Box::new(local_player_name.clone()));
+        }
+        if let Some(class_name) = &query.class_name {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM encounter_players p WHERE p.encounter_id = e.id AND p.class_name = ?)",
+            );
+            values.push(Box::new(class_name.clone()));
+        }
+        if let Some(class_spec_name) = &query.class_spec_name {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM encounter_players p WHERE p.encounter_id = e.id AND p.class_spec_name = ?)",
+            );
+            values.push(Box::new(class_spec_name.clone()));
+        }
+        if let Some(min_duration_ms) = query.min_duration_ms {
+            conditions.push("e.duration_ms >= ?");
+            values.push(Box::new(min_duration_ms));
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let total_count: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM encounters e {where_clause}"),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        let sort_column = match query.sort_by.unwrap_or_default() {
+            HistorySortBy::Date => "e.time_fight_start_ms",
+            HistorySortBy::Duration => "e.duration_ms",
+            HistorySortBy::TotalDmg => "e.total_dmg",
+            HistorySortBy::TotalDps => "CAST(e.total_dmg AS REAL) / MAX(e.duration_ms, 1)",
+        };
+        let sort_direction = if query.sort_ascending.unwrap_or(false) {
+            "ASC"
+        } else {
+            "DESC"
+        };
+        let page = query.page.unwrap_or(0);
+        let page_size = query
+            .page_size
+            .unwrap_or(DEFAULT_HISTORY_PAGE_SIZE)
+            .clamp(1, MAX_HISTORY_PAGE_SIZE);
+        values.push(Box::new(page_size));
+        values.push(Box::new(i64::from(page) * i64::from(page_size)));
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT e.id, e.time_fight_start_ms, e.time_last_combat_packet_ms, e.duration_ms, e.end_reason,
+                    e.total_dmg, e.total_heal, e.map_id, e.line_id, e.level_map_id, e.dungeon_guid,
+                    e.local_player_name,
+                    (SELECT GROUP_CONCAT(m.monster_id) FROM encounter_monsters m WHERE m.encounter_id = e.id),
+                    (SELECT COUNT(*) FROM encounter_players p WHERE p.encounter_id = e.id),
+                    (SELECT p.name FROM encounter_players p WHERE p.encounter_id = e.id ORDER BY p.total_dmg DESC LIMIT 1)
+             FROM encounters e {where_clause}
+             ORDER BY {sort_column} {sort_direction}, e.id {sort_direction}
+             LIMIT ? OFFSET ?"
+        ))?;
+        let summaries = stmt
+            .query_map(params_from_iter(values.iter()), |row| {
+                let monster_ids: Option<String> = row.get(12)?;
+                Ok(HistoryEncounterSummary {
+                    id: row.get(0)?,
+                    time_fight_start_ms: row.get(1)?,
+                    time_last_combat_packet_ms: row.get(2)?,
+                    duration_ms: row.get(3)?,
+                    end_reason: row.get(4)?,
+                    total_dmg: row.get(5)?,
+                    total_heal: row.get(6)?,
+                    map_id: row.get(7)?,
+                    line_id: row.get(8)?,
+                    level_map_id: row.get(9)?,
+                    dungeon_guid: row.get(10)?,
+                    local_player_name: row.get(11)?,
+                    monster_ids: monster_ids
+                        .unwrap_or_default()
+                        .split(',')
+                        .filter_map(|monster_id| monster_id.parse().ok())
+                        .collect(),
+                    player_count: row.get(13)?,
+                    top_player_name: row.get(14)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(HistoryPage {
+            summaries,
+            total_count,
+            page,
+            page_size,
+        })
+    }
+
+    pub fn load_encounter(&self, id: i64) -> rusqlite::Result<Option<SavedEncounter>> {
+        let row: Option<(u32, String)> = self
+            .conn
+            .query_row(
+                "SELECT data_version, data FROM encounters WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        row.map(|(data_version, data)| upgrade_saved_encounter(data_version, &data))
+            .transpose()
+    }
+
+    pub fn delete_encounter(&self, id: i64) -> rusqlite::Result<bool> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM encounters WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
+
+    /// Deletes every saved encounter in one statement, their players and monsters cascade
+    pub fn clear_encounters(&self) -> rusqlite::Result<usize> {
+        self.conn.execute("DELETE FROM encounters", [])
+    }
+}
+
+/// Fills the searchable columns and tables of an already inserted encounter
+fn index_encounter(
+    tx: &Transaction,
+    id: i64,
+    saved_encounter: &SavedEncounter,
+) -> rusqlite::Result<()> {
+    let location = saved_encounter.location.as_ref();
+    tx.execute(
+        "UPDATE encounters SET map_id = ?2, line_id = ?3, local_player_uid = ?4, local_player_name = ?5, level_map_id = ?6, dungeon_guid = ?7 WHERE id = ?1",
+        params![
+            id,
+            saved_encounter.map_id,
+            saved_encounter.line_id,
+            saved_encounter.local_player_uid,
+            saved_encounter.local_player_name,
+            location.and_then(|location| location.level_map_id),
+            location.and_then(|location| location.dungeon_guid.clone()),
+        ],
+    )?;
+    for saved_entity in &saved_encounter.entities {
+        if saved_entity.entity_type == EEntityType::EntChar as i32 {
+            tx.execute(
+                "INSERT OR REPLACE INTO encounter_players (encounter_id, player_uid, name, class_name, class_spec_name, total_dmg, total_heal)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    saved_entity.uid,
+                    saved_entity.name,
+                    class::get_class_name(saved_entity.class.unwrap_or(Class::Unknown)),
+                    class::get_class_spec(saved_entity.class_spec.unwrap_or(ClassSpec::Unknown)),
+                    saved_entity.dmg_stats.value,
+                    saved_entity.heal_stats.value,
+                ],
+            )?;
+        } else if let Some(monster_id) = saved_entity.monster_id {
+            tx.execute(
+                "INSERT OR IGNORE INTO encounter_monsters (encounter_id, monster_id) VALUES (?1, ?2)",
+                params![id, monster_id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Indexes encounters saved before the searchable columns existed
+fn reindex_encounters(tx: &Transaction) -> rusqlite::Result<()> {
+    let rows: Vec<(i64, u32, String)> = tx
+        .prepare("SELECT id, data_version, data FROM encounters")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (id, data_version, data) in rows {
+        match upgrade_saved_encounter(data_version, &data) {
+            Ok(saved_encounter) => index_encounter(tx, id, &saved_encounter)?,
+            Err(e) => warn!("Skipping unreadable encounter {id} while indexing history: {e}"),
+        }
+    }
+    Ok(())
+}
+
+/// Reads an encounter blob written by any past version of the app
+fn upgrade_saved_encounter(data_version: u32, data: &str) -> rusqlite::Result<SavedEncounter> {
+    let to_sql_error = |e: serde_json::Error| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    };
+    let value: serde_json::Value = serde_json::from_str(data).map_err(to_sql_error)?;
+    // Upgrade steps go here, e.g. `if data_version < 2 { value["new_field"] = ...; }`
+    if data_version > SAVED_ENCOUNTER_VERSION {
+        warn!("Encounter was saved by a newer version of the app (data version {data_version})");
+    }
+    serde_json::from_value(value).map_err(to_sql_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::db::{HistoryDb, HistoryQuery, MIGRATIONS};
+    use crate::history::saved_encounter::{SavedEncounter, SavedEntity};
+    use crate::live::location_tracker::Location;
+    use crate::live::opcodes_models::CombatStats;
+    use crate::live::opcodes_models::class::{Class, ClassSpec};
+    use blueprotobuf_lib::blueprotobuf::EEntityType;
+    use rusqlite::{Connection, params};
+
+    const MONSTER_ID: i32 = 10_010;
+
+    fn saved_encounter() -> SavedEncounter {
+        let dmg_stats = CombatStats {
+            value: 50_000,
+            hits: 10,
+            ..Default::default()
+        };
+        SavedEncounter {
+            time_fight_start_ms: 1_000,
+            time_last_combat_packet_ms: 11_000,
+            end_reason: "bossDeath".to_string(),
+            local_player_uid: Some(7),
+            local_player_name: Some("Player".to_string()),
+            map_id: Some(3),
+            line_id: Some(12),
+            location: Some(Location {
+                map_id: Some(3),
+                line_id: Some(12),
+                level_map_id: Some(301),
+                dungeon_guid: Some("dungeon-1".to_string()),
+                ..Default::default()
+            }),
+            dmg_stats: dmg_stats.clone(),
+            entities: vec![
+                SavedEntity {
+                    uid: 7,
+                    entity_type: EEntityType::EntChar as i32,
+                    name: Some("Player".to_string()),
+                    class: Some(Class::FrostMage),
+                    class_spec: Some(ClassSpec::Icicle),
+                    dmg_stats,
+                    ..Default::default()
+                },
+                SavedEntity {
+                    uid: 900,
+                    entity_type: EEntityType::EntMonster as i32,
+                    monster_id: Some(MONSTER_ID),
+                    max_hp: Some(50_000),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let mut history_db = HistoryDb::open_in_memory().unwrap();
+        let id = history_db.save_encounter(&saved_encounter()).unwrap();
+
+        let loaded_encounter = history_db.load_encounter(id).unwrap().unwrap();
+        assert_eq!(loaded_encounter.time_fight_start_ms, 1_000);
+        assert_eq!(loaded_encounter.end_reason, "bossDeath");
+        assert_eq!(loaded_encounter.location, saved_encounter().location);
+        assert_eq!(loaded_encounter.entities.len(), 2);
+        assert_eq!(loaded_encounter.dmg_stats.value, 50_000);
+
+        let page = history_db
+            .query_encounters(&HistoryQuery {
+                monster_id: Some(MONSTER_ID),
+                class_spec_name: Some("Icicle".to_string()),
+                dungeon_guid: Some("dungeon-1".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.total_count, 1);
+        assert_eq!(page.summaries[0].id, id);
+        assert_eq!(page.summaries[0].duration_ms, 10_000);
+        assert_eq!(page.summaries[0].top_player_name.as_deref(), Some("Player"));
+
+        assert!(history_db.delete_encounter(id).unwrap());
+        assert!(history_db.load_encounter(id).unwrap().is_none());
+
+        history_db.save_encounter(&saved_encounter()).unwrap();
+        history_db.save_encounter(&saved_encounter()).unwrap();
+        assert_eq!(history_db.clear_encounters().unwrap(), 2);
+        assert_eq!(
+            history_db
+                .query_encounters(&HistoryQuery::default())
+                .unwrap()
+                .total_count,
+            0
+        );
+    }
+
+    #[test]
+    fn test_migrate_from_v1() {
+        // An encounter saved before the searchable columns existed
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        conn.execute(
+            "INSERT INTO encounters (data_version, time_fight_start_ms, time_last_combat_packet_ms, duration_ms, end_reason, total_dmg, total_heal, data)
+             VALUES (1, 1000, 11000, 10000, 'bossDeath', 50000, 0, ?1)",
+            params![serde_json::to_string(&saved_encounter()).unwrap()],
+        )
+        .unwrap();
+
+        let history_db = HistoryDb::init(conn).unwrap();
+        let user_version: usize = history_db
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(user_version, MIGRATIONS.len());
+
+        let page = history_db
+            .query_encounters(&HistoryQuery {
+                monster_id: Some(MONSTER_ID),
+                map_id: Some(3),
+                class_name: Some("Frost Mage".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.total_count, 1);
+        let summary = &page.summaries[0];
+        assert_eq!(summary.line_id, Some(12));
+        assert_eq!(summary.level_map_id, Some(301));
+        assert_eq!(summary.local_player_name.as_deref(), Some("Player"));
+        assert_eq!(summary.monster_ids, vec![MONSTER_ID]);
+        assert!(history_db.load_encounter(summary.id).unwrap().is_some());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::live::character_profile::CharacterProfile;
+use crate::live::location_tracker::Location;
+use crate::live::opcodes_models::class::{Class, ClassSpec};
+use crate::live::opcodes_models::{CombatStats, Encounter, Entity};
+use blueprotobuf_lib::blueprotobuf::EEntityType;
+use std::collections::{BTreeMap, HashMap};
+
+// Bump whenever the serialized layout below changes and add an upgrade step in `history::db`
+pub const SAVED_ENCOUNTER_VERSION: u32 = 1;
+
+/// Completed encounter as it is written to the history database, maps are ordered so the same encounter always serializes to the same blob
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SavedEncounter {
+    pub time_fight_start_ms: u128,
+    pub time_last_combat_packet_ms: u128,
+    pub end_reason: String,
+    pub local_player_uid: Option<i64>,
+    #[serde(default)]
+    pub local_player_name: Option<String>,
+    #[serde(default)]
+    pub map_id: Option<u32>,
+    #[serde(default)]
+    pub line_id: Option<u32>,
+    #[serde(default)]
+    pub location: Option<Location>,
+    #[serde(default)]
+    pub character_profile: Option<CharacterProfile>, // local player's gear as of the last full SyncContainerData
+    pub dmg_stats: CombatStats,
+    pub dmg_stats_boss_only: CombatStats,
+    pub heal_stats: CombatStats,
+    pub entities: Vec<SavedEntity>,
+}
+
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SavedEntity {
+    pub uid: i64,
+    pub entity_type: i32, // EEntityType
+    pub name: Option<String>,
+    pub class: Option<Class>,
+    pub class_spec: Option<ClassSpec>,
+    pub ability_score: Option<i32>,
+    pub monster_id: Option<i32>,
+    pub max_hp: Option<i32>,
+
+    pub dmg_stats: CombatStats,
+    pub skill_uid_to_dps_stats: BTreeMap<i32, CombatStats>,
+    pub dmg_stats_boss_only: CombatStats,
+    pub skill_uid_to_dps_stats_boss_only: BTreeMap<i32, CombatStats>,
+    pub heal_stats: CombatStats,
+    pub skill_uid_to_heal_stats: BTreeMap<i32, CombatStats>,
+    #[serde(default)]
+    pub skill_uid_to_source_dps_stats: BTreeMap<i32, BTreeMap<i64, CombatStats>>,
+    #[serde(default)]
+    pub skill_uid_to_source_dps_stats_boss_only: BTreeMap<i32, BTreeMap<i64, CombatStats>>,
+    #[serde(default)]
+    pub skill_uid_to_source_heal_stats: BTreeMap<i32, BTreeMap<i64, CombatStats>>,
+}
+
+impl SavedEncounter {
+    pub fn from_encounter(encounter: &Encounter, end_reason: &str) -> Self {
+        let mut saved_entities: Vec<SavedEntity> = encounter
+            .entity_uid_to_entity
+            .iter()
+            // Only keep entities that took part: anything with stats and every tracked boss
+            .filter(|(entity_uid, entity)| {
+                entity.dmg_stats.hits > 0
+                    || entity.heal_stats.hits > 0
+                    || encounter
+                        .boss_tracker
+                        .boss_uid_to_hp_timeline
+                        .contains_key(entity_uid)
+            })
+            .map(|(&entity_uid, entity)| SavedEntity {
+                uid: entity_uid,
+                entity_type: entity.entity_type as i32,
+                name: entity.name.clone(),
+                class: entity.class,
+                class_spec: entity.class_spec,
+                ability_score: entity.ability_score,
+                monster_id: entity.monster_id,
+                max_hp: entity.max_hp,
+                dmg_stats: entity.dmg_stats.clone(),
+                skill_uid_to_dps_stats: entity.skill_uid_to_dps_stats.clone().into_iter().collect(),
+                dmg_stats_boss_only: entity.dmg_stats_boss_only.clone(),
+                skill_uid_to_dps_stats_boss_only: entity
+                    .skill_uid_to_dps_stats_boss_only
+                    .clone()
+                    .into_iter()
+                    .collect(),
+                heal_stats: entity.heal_stats.clone(),
+                skill_uid_to_heal_stats: entity
+                    .skill_uid_to_heal_stats
+                    .clone()
+                    .into_iter()
+                    .collect(),
+                skill_uid_to_source_dps_stats: sort_source_stats(
+                    &entity.skill_uid_to_source_dps_stats,
+                ),
+                skill_uid_to_source_dps_stats_boss_only: sort_source_stats(
+                    &entity.skill_uid_to_source_dps_stats_boss_only,
+                ),
+                skill_uid_to_source_heal_stats: sort_source_stats(
+                    &entity.skill_uid_to_source_heal_stats,
+                ),
+            })
+            .collect();
+        saved_entities.sort_by_key(|saved_entity| saved_entity.uid);
+        let v_data = encounter
+            .local_player
+            .as_ref()
+            .and_then(|local_player| local_player.v_data.as_ref());
+        Self {
+            time_fight_start_ms: encounter.time_fight_start_ms,
+            time_last_combat_packet_ms: encounter.time_last_combat_packet_ms,
+            end_reason: end_reason.to_string(),
+            local_player_uid: v_data.and_then(|v_data| v_data.char_id),
+            local_player_name: v_data
+                .and_then(|v_data| v_data.char_base.as_ref())
+                .and_then(|char_base| char_base.name.clone()),
+            map_id: encounter
+                .location
+                .as_ref()
+                .and_then(|location| location.map_id),
+            line_id: encounter
+                .location
+                .as_ref()
+                .and_then(|location| location.line_id),
+            location: encounter.location.clone(),
+            character_profile: v_data.and_then(|v_data| {
+                CharacterProfile::from_char_serialize(v_data, encounter.local_player_ms)
+            }),
+            dmg_stats: encounter.dmg_stats.clone(),
+            dmg_stats_boss_only: encounter.dmg_stats_boss_only.clone(),
+            heal_stats: encounter.heal_stats.clone(),
+            entities: saved_entities,
+        }
+    }
+
+    /// Rebuilds an `Encounter` so the live window functions can render saved encounters
+    pub fn to_encounter(&self) -> Encounter {
+        Encounter {
+            time_fight_start_ms: self.time_fight_start_ms,
+            time_last_combat_packet_ms: self.time_last_combat_packet_ms,
+            dmg_stats: self.dmg_stats.clone(),
+            dmg_stats_boss_only: self.dmg_stats_boss_only.clone(),
+            heal_stats: self.heal_stats.clone(),
+            entity_uid_to_entity: self
+                .entities
+                .iter()
+                .map(|saved_entity| {
+                    let entity = Entity {
+                        entity_type: EEntityType::try_from(saved_entity.entity_type)
+                            .unwrap_or_default(),
+                        name: saved_entity.name.clone(),
+                        class: saved_entity.class,
+                        class_spec: saved_entity.class_spec,
+                        ability_score: saved_entity.ability_score,
+                        monster_id: saved_entity.monster_id,
+                        max_hp: saved_entity.max_hp,
+                        dmg_stats: saved_entity.dmg_stats.clone(),
+                        skill_uid_to_dps_stats: saved_entity
+                            .skill_uid_to_dps_stats
+                            .clone()
+                            .into_iter()
+                            .collect(),
+                        dmg_stats_boss_only: saved_entity.dmg_stats_boss_only.clone(),
+                        skill_uid_to_dps_stats_boss_only: saved_entity
+                            .skill_uid_to_dps_stats_boss_only
+                            .clone()
+                            .into_iter()
+                            .collect(),
+                        heal_stats: saved_entity.heal_stats.clone(),
+                        skill_uid_to_heal_stats: saved_entity
+                            .skill_uid_to_heal_stats
+                            .clone()
+                            .into_iter()
+                            .collect(),
+                        skill_uid_to_source_dps_stats: unsort_source_stats(
+                            &saved_entity.skill_uid_to_source_dps_stats,
+                        ),
+                        skill_uid_to_source_dps_stats_boss_only: unsort_source_stats(
+                            &saved_entity.skill_uid_to_source_dps_stats_boss_only,
+                        ),
+                        skill_uid_to_source_heal_stats: unsort_source_stats(
+                            &saved_entity.skill_uid_to_source_heal_stats,
+                        ),
+                        ..Default::default()
+                    };
+                    (saved_entity.uid, entity)
+                })
+                .collect(),
+            location: self.location.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+fn sort_source_stats(
+    skill_uid_to_source_stats: &HashMap<i32, HashMap<i64, CombatStats>>,
+) -> BTreeMap<i32, BTreeMap<i64, CombatStats>> {
+    skill_uid_to_source_stats
+        .iter()
+        .map(|(&skill_uid, source_uid_to_stats)| {
+            let source_uid_to_stats = source_uid_to_stats
+                .iter()
+                .map(|(&source_uid, stats)| (source_uid, stats.clone()))
+                .collect();
+            (skill_uid, source_uid_to_stats)
+        })
+        .collect()
+}
+
+fn unsort_source_stats(
+    skill_uid_to_source_stats: &BTreeMap<i32, BTreeMap<i64, CombatStats>>,
+) -> HashMap<i32, HashMap<i64, CombatStats>> {
+    skill_uid_to_source_stats
+        .iter()
+        .map(|(&skill_uid, source_uid_to_stats)| {
+            let source_uid_to_stats = source_uid_to_stats
+                .iter()
+                .map(|(&source_uid, stats)| (source_uid, stats.clone()))
+                .collect();
+            (skill_uid, source_uid_to_stats)
+        })
+        .collect()
+}
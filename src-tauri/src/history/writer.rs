@@ -0,0 +1,81 @@
+use crate::history::db::HistoryDbMutex;
+use crate::history::saved_encounter::SavedEncounter;
+use crate::live::opcodes_models::Encounter;
+use log::warn;
+use std::sync::{Arc, mpsc};
+
+/// Saves completed encounters on its own thread so the packet loop never waits on serialization or SQLite
+#[derive(Debug)]
+pub struct HistoryWriter {
+    save_sender: mpsc::Sender<(u32, Arc<Encounter>, &'static str)>,
+    saved_receiver: mpsc::Receiver<(u32, i64)>, // (encounter id, history db id)
+}
+
+impl HistoryWriter {
+    /// The thread exits once the writer is dropped and everything queued has been saved
+    pub fn spawn(history_db: HistoryDbMutex) -> Self {
+        let (save_sender, save_receiver) = mpsc::channel::<(u32, Arc<Encounter>, &'static str)>();
+        let (saved_sender, saved_receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (id, encounter, end_reason) in save_receiver {
+                let saved_encounter = SavedEncounter::from_encounter(&encounter, end_reason);
+                match history_db.lock().unwrap().save_encounter(&saved_encounter) {
+                    Ok(saved_id) => {
+                        let _ = saved_sender.send((id, saved_id));
+                    }
+                    Err(e) => warn!("Failed to save encounter {id} to history: {e}"),
+                }
+            }
+        });
+        Self {
+            save_sender,
+            saved_receiver,
+        }
+    }
+
+    pub fn save(&self, id: u32, encounter: Arc<Encounter>, end_reason: &'static str) {
+        if self.save_sender.send((id, encounter, end_reason)).is_err() {
+            warn!("History writer stopped, encounter {id} won't be saved");
+        }
+    }
+
+    /// Encounters saved since the last call, as (encounter id, history db id)
+    pub fn take_saved(&self) -> impl Iterator<Item = (u32, i64)> + '_ {
+        self.saved_receiver.try_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::db::{HistoryDb, HistoryDbMutex};
+    use crate::history::writer::HistoryWriter;
+    use crate::live::opcodes_models::Encounter;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn test_save_on_writer_thread() {
+        let history_db = HistoryDbMutex::new(Mutex::new(HistoryDb::open_in_memory().unwrap()));
+        let history_writer = HistoryWriter::spawn(history_db.clone());
+        let encounter = Encounter {
+            time_fight_start_ms: 1_000,
+            time_last_combat_packet_ms: 9_000,
+            ..Default::default()
+        };
+        history_writer.save(3, Arc::new(encounter), "reset");
+
+        let (id, saved_id) = history_writer
+            .saved_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(id, 3);
+        let saved_encounter = history_db
+            .lock()
+            .unwrap()
+            .load_encounter(saved_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(saved_encounter.time_last_combat_packet_ms, 9_000);
+        assert_eq!(saved_encounter.end_reason, "reset");
+    }
+}
@@ -1,8 +1,8 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use log::{info, warn};
@@ -10,31 +10,52 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::history::db::{HistoryDbMutex, HistoryQuery};
+use crate::live::boss_tracker::DEFAULT_PHASE_THRESHOLDS_PCT;
 use crate::live::bptimer_state::BPTimerEnabledMutex;
-use crate::live::commands::{get_player_window, get_skill_window_impl, StatType};
+use crate::live::commands::{
+    get_body_part_window_impl, get_boss_window_impl, get_buff_window_impl,
+    get_character_profile_impl, get_cooldown_window_impl, get_death_recap_impl,
+    get_deaths_window_impl, get_dungeon_runs_impl, get_encounter_history_impl, get_hate_window_impl,
+    get_history_character_profile_impl, get_history_impl, get_last_boss_pull_ms, get_location_impl,
+    get_player_window, get_skill_window_impl, get_targets_impl, get_timeline_impl,
+    load_history_encounter, SkillGrouping, StatType,
+};
+use crate::live::death_tracker::RECENT_INCOMING_CAPACITY;
+use crate::live::encounter_manager::{EncounterEndReason, EncounterManagerMutex};
 use crate::live::opcodes_models::{Encounter, EncounterMutex};
 use crate::live::player_state::{PlayerCacheMutex, PlayerStateMutex};
+use crate::live::rolling_window_state::{get_rolling_window_secs, RollingWindowSecsState};
 
 pub struct AppState {
     pub encounter: EncounterMutex,
+    pub encounter_manager: EncounterManagerMutex,
     pub player_state: PlayerStateMutex,
     pub player_cache: PlayerCacheMutex,
     pub bptimer_enabled: BPTimerEnabledMutex,
+    pub rolling_window_secs: RollingWindowSecsState,
+    pub history_db: HistoryDbMutex,
 }
 
 pub async fn start_http_server(
     encounter: EncounterMutex,
+    encounter_manager: EncounterManagerMutex,
     player_state: PlayerStateMutex,
     player_cache: PlayerCacheMutex,
     bptimer_enabled: BPTimerEnabledMutex,
+    rolling_window_secs: RollingWindowSecsState,
+    history_db: HistoryDbMutex,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("🔧 Building HTTP API server state...");
     
     let state = Arc::new(AppState {
         encounter,
+        encounter_manager,
         player_state,
         player_cache,
         bptimer_enabled,
+        rolling_window_secs,
+        history_db,
     });
 
     info!("🔧 Configuring CORS...");
@@ -63,6 +84,41 @@ pub async fn start_http_server(
             "/heal-skill-window/:player_uid",
             get(api_get_heal_skill_window),
         )
+        .route("/buff-window", get(api_get_buff_window))
+        .route("/deaths-window", get(api_get_deaths_window))
+        .route("/cooldown-window", get(api_get_cooldown_window))
+        .route("/hate-window", get(api_get_hate_window))
+        .route("/targets", get(api_get_targets))
+        .route("/body-part-window", get(api_get_body_part_window))
+        .route("/character-profile", get(api_get_character_profile))
+        .route("/location", get(api_get_location))
+        .route("/death-recap/:player_uid", get(api_get_death_recap))
+        .route("/boss-window", get(api_get_boss_window))
+        .route("/timeline", get(api_get_timeline))
+        .route("/encounters", get(api_get_encounters))
+        .route("/dungeon-runs", get(api_get_dungeon_runs))
+        .route(
+            "/encounters/:encounter_id/player-window",
+            get(api_get_encounter_player_window),
+        )
+        .route(
+            "/encounters/:encounter_id/skill-window/:player_uid",
+            get(api_get_encounter_skill_window),
+        )
+        .route("/history", get(api_get_history).delete(api_clear_history))
+        .route("/history/:encounter_id", delete(api_delete_history_encounter))
+        .route(
+            "/history/:encounter_id/player-window",
+            get(api_get_history_player_window),
+        )
+        .route(
+            "/history/:encounter_id/skill-window/:player_uid",
+            get(api_get_history_skill_window),
+        )
+        .route(
+            "/history/:encounter_id/character-profile",
+            get(api_get_history_character_profile),
+        )
         .route("/test-player-window", get(api_get_test_player_window))
         .route(
             "/test-skill-window/:player_uid",
@@ -71,7 +127,12 @@ pub async fn start_http_server(
         .route("/reset-encounter", post(api_reset_encounter))
         .route("/toggle-pause-encounter", post(api_toggle_pause_encounter))
         .route("/hard-reset", post(api_hard_reset))
-        .route("/set-bptimer-enabled", post(api_set_bptimer_enabled));
+        .route("/set-bptimer-enabled", post(api_set_bptimer_enabled))
+        .route("/set-rolling-window-secs", post(api_set_rolling_window_secs))
+        .route(
+            "/set-encounter-inactivity-timeout",
+            post(api_set_encounter_inactivity_timeout),
+        );
 
     info!("🔧 Creating main router with CORS layer...");
     let app = Router::new()
@@ -126,7 +187,10 @@ pub async fn start_http_server(
     }
 }
 
-fn get_header_info_from_encounter(encounter: &Encounter) -> Result<serde_json::Value, String> {
+fn get_header_info_from_encounter(
+    encounter: &Encounter,
+    rolling_window_secs: u32,
+) -> Result<serde_json::Value, String> {
     if encounter.dmg_stats.value == 0 {
         return Err("No damage found".to_string());
     }
@@ -148,6 +212,13 @@ fn get_header_info_from_encounter(encounter: &Encounter) -> Result<serde_json::V
         "totalDmg": encounter_stats.value as f64,
         "elapsedMs": time_elapsed_ms as f64,
         "timeLastCombatPacketMs": encounter.time_last_combat_packet_ms as f64,
+        "rollingDps": encounter.timeline_tracker.get_rolling_value_per_sec(
+            encounter.timeline_tracker.entity_uid_to_dmg.values(),
+            encounter.get_now_ms(),
+            rolling_window_secs,
+        ),
+        "rollingWindowSecs": f64::from(rolling_window_secs),
+        "lastBossPullMs": get_last_boss_pull_ms(encounter),
     }))
 }
 
@@ -155,7 +226,8 @@ async fn api_get_header_info(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let encounter = state.encounter.lock().unwrap();
-    match get_header_info_from_encounter(&encounter) {
+    let rolling_window_secs = get_rolling_window_secs(&state.rolling_window_secs);
+    match get_header_info_from_encounter(&encounter, rolling_window_secs) {
         Ok(info) => Ok(Json(info)),
         Err(e) => {
             warn!("Error getting header info: {}", e);
@@ -168,26 +240,41 @@ async fn api_get_dps_player_window(
     State(state): State<Arc<AppState>>,
 ) -> Json<serde_json::Value> {
     let encounter = state.encounter.lock().unwrap();
-    let player_cache = state.player_cache.lock().unwrap();
     let player_state = state.player_state.lock().unwrap();
-    let result = get_player_window(encounter, StatType::Dmg, &player_cache, &player_state);
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_player_window(
+        &encounter,
+        StatType::Dmg,
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
+    );
     Json(serde_json::to_value(result).unwrap())
 }
 
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SkillWindowQuery {
+    grouping: Option<SkillGrouping>,
+}
+
 async fn api_get_dps_skill_window(
     State(state): State<Arc<AppState>>,
     Path(player_uid): Path<String>,
+    Query(query): Query<SkillWindowQuery>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let player_uid_i64 = player_uid.parse::<i64>().map_err(|_| StatusCode::BAD_REQUEST)?;
     let encounter = state.encounter.lock().unwrap();
-    let player_cache = state.player_cache.lock().unwrap();
     let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
     match get_skill_window_impl(
-        encounter,
+        &encounter,
         player_uid_i64,
         StatType::Dmg,
+        query.grouping.unwrap_or_default(),
         &player_cache,
         &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
     ) {
         Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
         Err(e) => {
@@ -201,13 +288,14 @@ async fn api_get_dps_boss_only_player_window(
     State(state): State<Arc<AppState>>,
 ) -> Json<serde_json::Value> {
     let encounter = state.encounter.lock().unwrap();
-    let player_cache = state.player_cache.lock().unwrap();
     let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
     let result = get_player_window(
-        encounter,
+        &encounter,
         StatType::DmgBossOnly,
         &player_cache,
         &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
     );
     Json(serde_json::to_value(result).unwrap())
 }
@@ -215,17 +303,20 @@ async fn api_get_dps_boss_only_player_window(
 async fn api_get_dps_boss_only_skill_window(
     State(state): State<Arc<AppState>>,
     Path(player_uid): Path<String>,
+    Query(query): Query<SkillWindowQuery>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let player_uid_i64 = player_uid.parse::<i64>().map_err(|_| StatusCode::BAD_REQUEST)?;
     let encounter = state.encounter.lock().unwrap();
-    let player_cache = state.player_cache.lock().unwrap();
     let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
     match get_skill_window_impl(
-        encounter,
+        &encounter,
         player_uid_i64,
         StatType::DmgBossOnly,
+        query.grouping.unwrap_or_default(),
         &player_cache,
         &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
     ) {
         Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
         Err(e) => {
@@ -239,26 +330,35 @@ async fn api_get_heal_player_window(
     State(state): State<Arc<AppState>>,
 ) -> Json<serde_json::Value> {
     let encounter = state.encounter.lock().unwrap();
-    let player_cache = state.player_cache.lock().unwrap();
     let player_state = state.player_state.lock().unwrap();
-    let result = get_player_window(encounter, StatType::Heal, &player_cache, &player_state);
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_player_window(
+        &encounter,
+        StatType::Heal,
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
+    );
     Json(serde_json::to_value(result).unwrap())
 }
 
 async fn api_get_heal_skill_window(
     State(state): State<Arc<AppState>>,
     Path(player_uid): Path<String>,
+    Query(query): Query<SkillWindowQuery>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let player_uid_i64 = player_uid.parse::<i64>().map_err(|_| StatusCode::BAD_REQUEST)?;
     let encounter = state.encounter.lock().unwrap();
-    let player_cache = state.player_cache.lock().unwrap();
     let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
     match get_skill_window_impl(
-        encounter,
+        &encounter,
         player_uid_i64,
         StatType::Heal,
+        query.grouping.unwrap_or_default(),
         &player_cache,
         &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
     ) {
         Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
         Err(e) => {
@@ -268,6 +368,154 @@ async fn api_get_heal_skill_window(
     }
 }
 
+async fn api_get_buff_window(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let encounter = state.encounter.lock().unwrap();
+    let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_buff_window_impl(&encounter, &player_cache, &player_state);
+    Json(serde_json::to_value(result).unwrap())
+}
+
+async fn api_get_deaths_window(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let encounter = state.encounter.lock().unwrap();
+    let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_deaths_window_impl(&encounter, &player_cache, &player_state);
+    Json(serde_json::to_value(result).unwrap())
+}
+
+async fn api_get_cooldown_window(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let encounter = state.encounter.lock().unwrap();
+    let player_state = state.player_state.lock().unwrap();
+    let result = get_cooldown_window_impl(&encounter, &player_state);
+    Json(serde_json::to_value(result).unwrap())
+}
+
+async fn api_get_hate_window(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let encounter = state.encounter.lock().unwrap();
+    let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_hate_window_impl(&encounter, &player_cache, &player_state);
+    Json(serde_json::to_value(result).unwrap())
+}
+
+async fn api_get_targets(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let encounter = state.encounter.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_targets_impl(&encounter, &player_cache);
+    Json(serde_json::to_value(result).unwrap())
+}
+
+async fn api_get_body_part_window(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let encounter = state.encounter.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_body_part_window_impl(&encounter, &player_cache);
+    Json(serde_json::to_value(result).unwrap())
+}
+
+async fn api_get_character_profile(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let encounter = state.encounter.lock().unwrap();
+    match get_character_profile_impl(&encounter) {
+        Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
+        Err(e) => {
+            warn!("Error getting character profile: {}", e);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+async fn api_get_location(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let player_state = state.player_state.lock().unwrap();
+    match get_location_impl(&player_state) {
+        Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
+        Err(e) => {
+            warn!("Error getting location: {}", e);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeathRecapQuery {
+    max_events: Option<usize>,
+}
+
+async fn api_get_death_recap(
+    State(state): State<Arc<AppState>>,
+    Path(player_uid): Path<String>,
+    Query(query): Query<DeathRecapQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let player_uid_i64 = player_uid.parse::<i64>().map_err(|_| StatusCode::BAD_REQUEST)?;
+    let encounter = state.encounter.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    match get_death_recap_impl(
+        &encounter,
+        &player_cache,
+        player_uid_i64,
+        query.max_events.unwrap_or(RECENT_INCOMING_CAPACITY),
+    ) {
+        Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
+        Err(e) => {
+            warn!("Error getting death recap: {}", e);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BossWindowQuery {
+    // Comma separated HP percentages, e.g. "75,50,25"
+    phase_thresholds: Option<String>,
+}
+
+async fn api_get_boss_window(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BossWindowQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let phase_thresholds_pct = match query.phase_thresholds {
+        Some(phase_thresholds) => phase_thresholds
+            .split(',')
+            .filter(|threshold| !threshold.trim().is_empty())
+            .map(|threshold| threshold.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => DEFAULT_PHASE_THRESHOLDS_PCT.to_vec(),
+    };
+    let encounter = state.encounter.lock().unwrap();
+    let result = get_boss_window_impl(&encounter, &phase_thresholds_pct);
+    Ok(Json(serde_json::to_value(result).unwrap()))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimelineQuery {
+    stat_type: Option<StatType>,
+    bucket_secs: Option<u32>,
+    max_points: Option<u32>,
+}
+
+async fn api_get_timeline(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TimelineQuery>,
+) -> Json<serde_json::Value> {
+    let encounter = state.encounter.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_timeline_impl(
+        &encounter,
+        &player_cache,
+        query.stat_type.unwrap_or(StatType::Dmg),
+        query.bucket_secs.unwrap_or(1),
+        query.max_points,
+    );
+    Json(serde_json::to_value(result).unwrap())
+}
+
 async fn api_get_test_player_window(
     State(_state): State<Arc<AppState>>,
 ) -> Json<serde_json::Value> {
@@ -292,7 +540,8 @@ async fn api_get_test_skill_window(
 
 async fn api_reset_encounter(State(state): State<Arc<AppState>>) -> StatusCode {
     let mut encounter = state.encounter.lock().unwrap();
-    encounter.clone_from(&Encounter::default());
+    let mut encounter_manager = state.encounter_manager.lock().unwrap();
+    encounter_manager.close_encounter(&mut encounter, EncounterEndReason::Reset);
     info!("Encounter reset via HTTP API");
     StatusCode::OK
 }
@@ -327,3 +576,216 @@ async fn api_set_bptimer_enabled(
     info!("BPTimer enabled set to {} via HTTP API", payload.enabled);
     StatusCode::OK
 }
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetRollingWindowSecsRequest {
+    window_secs: u32,
+}
+
+async fn api_set_rolling_window_secs(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SetRollingWindowSecsRequest>,
+) -> StatusCode {
+    use crate::live::rolling_window_state::set_rolling_window_secs;
+    set_rolling_window_secs(&state.rolling_window_secs, payload.window_secs);
+    info!("Rolling DPS window set to {}s via HTTP API", payload.window_secs);
+    StatusCode::OK
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncounterWindowQuery {
+    stat_type: Option<StatType>,
+    grouping: Option<SkillGrouping>, // skill windows only
+}
+
+async fn api_get_encounters(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let encounter_manager = state.encounter_manager.lock().unwrap();
+    let result = get_encounter_history_impl(&encounter_manager.history);
+    Json(serde_json::to_value(result).unwrap())
+}
+
+async fn api_get_dungeon_runs(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let encounter_manager = state.encounter_manager.lock().unwrap();
+    let result = get_dungeon_runs_impl(&encounter_manager.dungeon_tracker);
+    Json(serde_json::to_value(result).unwrap())
+}
+
+async fn api_get_encounter_player_window(
+    State(state): State<Arc<AppState>>,
+    Path(encounter_id): Path<u32>,
+    Query(query): Query<EncounterWindowQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let encounter_manager = state.encounter_manager.lock().unwrap();
+    let completed_encounter = encounter_manager
+        .get(encounter_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_player_window(
+        &completed_encounter.encounter,
+        query.stat_type.unwrap_or(StatType::Dmg),
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
+    );
+    Ok(Json(serde_json::to_value(result).unwrap()))
+}
+
+async fn api_get_encounter_skill_window(
+    State(state): State<Arc<AppState>>,
+    Path((encounter_id, player_uid)): Path<(u32, String)>,
+    Query(query): Query<EncounterWindowQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let player_uid_i64 = player_uid.parse::<i64>().map_err(|_| StatusCode::BAD_REQUEST)?;
+    let encounter_manager = state.encounter_manager.lock().unwrap();
+    let completed_encounter = encounter_manager
+        .get(encounter_id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    match get_skill_window_impl(
+        &completed_encounter.encounter,
+        player_uid_i64,
+        query.stat_type.unwrap_or(StatType::Dmg),
+        query.grouping.unwrap_or_default(),
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
+    ) {
+        Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
+        Err(e) => {
+            warn!("Error getting encounter skill window: {}", e);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+async fn api_get_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let history_db = state.history_db.lock().unwrap();
+    match get_history_impl(&history_db, &query) {
+        Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
+        Err(e) => {
+            warn!("Error getting encounter history: {}", e);
+            Err(StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+async fn api_get_history_player_window(
+    State(state): State<Arc<AppState>>,
+    Path(encounter_id): Path<u32>,
+    Query(query): Query<EncounterWindowQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let encounter = load_history_encounter(&state.history_db.lock().unwrap(), encounter_id)
+        .map_err(|e| {
+            warn!("Error loading saved encounter: {}", e);
+            StatusCode::NOT_FOUND
+        })?;
+    let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    let result = get_player_window(
+        &encounter,
+        query.stat_type.unwrap_or(StatType::Dmg),
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
+    );
+    Ok(Json(serde_json::to_value(result).unwrap()))
+}
+
+async fn api_get_history_skill_window(
+    State(state): State<Arc<AppState>>,
+    Path((encounter_id, player_uid)): Path<(u32, String)>,
+    Query(query): Query<EncounterWindowQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let player_uid_i64 = player_uid.parse::<i64>().map_err(|_| StatusCode::BAD_REQUEST)?;
+    let encounter = load_history_encounter(&state.history_db.lock().unwrap(), encounter_id)
+        .map_err(|e| {
+            warn!("Error loading saved encounter: {}", e);
+            StatusCode::NOT_FOUND
+        })?;
+    let player_state = state.player_state.lock().unwrap();
+    let player_cache = state.player_cache.lock().unwrap();
+    match get_skill_window_impl(
+        &encounter,
+        player_uid_i64,
+        query.stat_type.unwrap_or(StatType::Dmg),
+        query.grouping.unwrap_or_default(),
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&state.rolling_window_secs),
+    ) {
+        Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
+        Err(e) => {
+            warn!("Error getting saved encounter skill window: {}", e);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+async fn api_get_history_character_profile(
+    State(state): State<Arc<AppState>>,
+    Path(encounter_id): Path<u32>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match get_history_character_profile_impl(&state.history_db.lock().unwrap(), encounter_id) {
+        Ok(result) => Ok(Json(serde_json::to_value(result).unwrap())),
+        Err(e) => {
+            warn!("Error getting saved encounter character profile: {}", e);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
+
+async fn api_delete_history_encounter(
+    State(state): State<Arc<AppState>>,
+    Path(encounter_id): Path<u32>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state
+        .history_db
+        .lock()
+        .unwrap()
+        .delete_encounter(i64::from(encounter_id))
+    {
+        Ok(deleted) => Ok(Json(serde_json::Value::Bool(deleted))),
+        Err(e) => {
+            warn!("Error deleting saved encounter {}: {}", encounter_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn api_clear_history(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.history_db.lock().unwrap().clear_encounters() {
+        Ok(_) => Ok(Json(serde_json::Value::Null)),
+        Err(e) => {
+            warn!("Error clearing history: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetEncounterInactivityTimeoutRequest {
+    timeout_secs: u32,
+}
+
+async fn api_set_encounter_inactivity_timeout(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SetEncounterInactivityTimeoutRequest>,
+) -> StatusCode {
+    let mut encounter_manager = state.encounter_manager.lock().unwrap();
+    encounter_manager.inactivity_timeout_ms = u128::from(payload.timeout_secs) * 1000;
+    info!(
+        "Encounter inactivity timeout set to {}s via HTTP API",
+        payload.timeout_secs
+    );
+    StatusCode::OK
+}
@@ -1,17 +1,25 @@
 mod build_app;
+mod history;
 mod http_server;
 mod live;
 mod packets;
 mod static_server;
 
 use crate::build_app::build;
+use crate::history::db::{HistoryDb, HistoryDbMutex};
 use crate::live::bptimer_state::create_bptimer_enabled;
+use crate::live::class_spec_table::load_class_spec_overrides;
+use crate::live::encounter_manager::{EncounterManager, EncounterManagerMutex};
 use crate::live::opcodes_models::EncounterMutex;
 use crate::live::player_state::{PlayerCacheMutex, PlayerStateMutex};
+use crate::live::rolling_window_state::{
+    create_rolling_window_secs, RollingWindowSecsState, DEFAULT_ROLLING_WINDOW_SECS,
+};
 use chrono::Utc;
 use log::{info, warn};
 use std::fs;
 use std::process::Command;
+use std::sync::Mutex;
 
 use crate::live::commands::{disable_blur, enable_blur};
 use tauri::menu::MenuBuilder;
@@ -52,6 +60,10 @@ pub fn run() {
             live::commands::enable_blur,
             live::commands::disable_blur,
             live::commands::copy_sync_container_data,
+            live::commands::dump_event_log,
+            live::commands::dump_encounter_event_log,
+            live::commands::get_character_profile,
+            live::commands::get_location,
             live::commands::get_header_info,
             live::commands::get_dps_player_window,
             live::commands::get_dps_skill_window,
@@ -59,12 +71,33 @@ pub fn run() {
             live::commands::get_dps_boss_only_skill_window,
             live::commands::get_heal_player_window,
             live::commands::get_heal_skill_window,
+            live::commands::get_buff_window,
+            live::commands::get_deaths_window,
+            live::commands::get_cooldown_window,
+            live::commands::get_hate_window,
+            live::commands::get_targets,
+            live::commands::get_body_part_window,
+            live::commands::get_death_recap,
+            live::commands::get_boss_window,
+            live::commands::get_timeline,
+            live::commands::set_encounter_inactivity_timeout,
+            live::commands::get_encounter_history,
+            live::commands::get_dungeon_runs,
+            live::commands::get_encounter_player_window,
+            live::commands::get_encounter_skill_window,
+            live::commands::get_history,
+            live::commands::get_history_player_window,
+            live::commands::get_history_skill_window,
+            live::commands::get_history_character_profile,
+            live::commands::delete_history_encounter,
+            live::commands::clear_history,
             live::commands::reset_encounter,
             live::commands::toggle_pause_encounter,
             live::commands::hard_reset,
             live::commands::get_test_player_window,
             live::commands::get_test_skill_window,
             live::commands::set_bptimer_enabled,
+            live::commands::set_rolling_window_secs,
         ]);
 
     #[cfg(debug_assertions)] // <- Only export on non-release builds
@@ -112,17 +145,37 @@ pub fn run() {
             // https://v2.tauri.app/learn/splashscreen/#start-some-setup-tasks
             let is_bptimer_enabled = app.svelte().get_or::<bool>("integration", "bptimer", true);
             app.manage(create_bptimer_enabled(is_bptimer_enabled)); // setup bptimer enabled state
+            // Lock order: Encounter -> EncounterManager -> PlayerState -> PlayerCache, the history db and settings states are only locked on their own
             app.manage(EncounterMutex::default()); // setup encounter state
+            if let Ok(app_data_dir) = app.path().app_data_dir() {
+                load_class_spec_overrides(&app_data_dir); // user overrides for spec inference
+            }
+            let history_db = HistoryDbMutex::new(Mutex::new(match app.path().app_data_dir() {
+                Ok(app_data_dir) => HistoryDb::open_or_in_memory(&app_data_dir),
+                Err(_) => HistoryDb::open_in_memory().expect("failed to open in-memory history db"),
+            }));
+            app.manage(history_db.clone()); // setup encounter history db
+            let reset_elapsed_secs = app.svelte().get_or::<u64>("general", "resetElapsed", 60);
+            app.manage(EncounterManagerMutex::new(Mutex::new(EncounterManager::new(
+                u128::from(reset_elapsed_secs) * 1000,
+                Some(history_db),
+            )))); // setup encounter manager (segmentation + history)
             app.manage(PlayerStateMutex::default()); // setup player state
             app.manage(PlayerCacheMutex::default()); // setup player cache
+            let rolling_window = app.svelte().get_or::<String>("general", "rollingWindow", format!("{DEFAULT_ROLLING_WINDOW_SECS}s"));
+            let rolling_window_secs = rolling_window.trim_end_matches('s').parse().unwrap_or(DEFAULT_ROLLING_WINDOW_SECS);
+            app.manage(create_rolling_window_secs(rolling_window_secs)); // setup rolling DPS window
             
             info!("🌐 Initializing web servers for browser access...");
             
             // Clone state for HTTP server
             let encounter_http = app.state::<EncounterMutex>().inner().clone();
+            let encounter_manager_http = app.state::<EncounterManagerMutex>().inner().clone();
             let player_state_http = app.state::<PlayerStateMutex>().inner().clone();
             let player_cache_http = app.state::<PlayerCacheMutex>().inner().clone();
             let bptimer_enabled_http = app.state::<crate::live::bptimer_state::BPTimerEnabledMutex>().inner().clone();
+            let rolling_window_secs_http = app.state::<RollingWindowSecsState>().inner().clone();
+            let history_db_http = app.state::<HistoryDbMutex>().inner().clone();
             
             // Start HTTP API server for web browser access (port 3000-3010)
             // This runs in a separate async task to avoid blocking application initialization
@@ -136,9 +189,12 @@ pub fn run() {
                 
                 match http_server::start_http_server(
                     encounter_http,
+                    encounter_manager_http,
                     player_state_http,
                     player_cache_http,
                     bptimer_enabled_http,
+                    rolling_window_secs_http,
+                    history_db_http,
                 )
                 .await
                 {
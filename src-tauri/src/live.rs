@@ -1,10 +1,28 @@
 // https://doc.rust-lang.org/reference/items/modules.html#module-source-filenames
 // Preferred way is to name modules with their subfolder name now (no longer mod.rs)
+pub mod body_part_tracker;
+pub mod boss_tracker;
 pub mod bptimer;
 pub mod bptimer_state;
+pub mod buff_tracker;
+pub mod character_profile;
+pub mod class_spec_table;
+pub mod clock;
 pub mod commands;
 mod commands_models;
+pub mod cooldown_tracker;
+pub mod damage_flags;
+pub mod death_tracker;
+pub mod dungeon_tracker;
+pub mod encounter_manager;
+pub mod event_log;
+pub mod hate_tracker;
 pub mod live_main;
+pub mod location_tracker;
 pub mod opcodes_models;
 mod opcodes_process;
+pub mod passive_tracker;
 pub mod player_state;
+pub mod rolling_window_state;
+pub mod skill_metadata;
+pub mod timeline_tracker;
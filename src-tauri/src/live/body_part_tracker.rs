@@ -0,0 +1,199 @@
+use blueprotobuf_lib::blueprotobuf;
+use blueprotobuf_lib::blueprotobuf::BodyPartState;
+use log::info;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone)]
+pub struct PartDmg {
+    pub value: i64,
+    pub hits: i64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BodyPart {
+    pub hp: Option<u64>,
+    pub max_hp: Option<u64>,
+    pub state: BodyPartState,
+    pub state_changes: Vec<(u128, BodyPartState)>, // oldest first
+    pub attacker_uid_to_dmg: HashMap<i64, PartDmg>,
+}
+
+impl BodyPart {
+    /// First time the part was destroyed
+    pub fn broken_ms(&self) -> Option<u128> {
+        self.state_changes
+            .iter()
+            .find(|(_, state)| *state == BodyPartState::Dead)
+            .map(|&(timestamp_ms, _)| timestamp_ms)
+    }
+}
+
+/// Breakable body parts per entity (mostly bosses), with the damage each attacker dealt to them
+#[derive(Debug, Default, Clone)]
+pub struct BodyPartTracker {
+    pub entity_uid_to_parts: HashMap<i64, HashMap<i32, BodyPart>>, // part_id => part
+}
+
+impl BodyPartTracker {
+    pub fn process_body_part_infos(
+        &mut self,
+        actor_body_part_infos: blueprotobuf::ActorBodyPartInfos,
+        default_entity_uuid: i64,
+        timestamp_ms: u128,
+    ) {
+        let entity_uid = actor_body_part_infos.uuid.unwrap_or(default_entity_uuid) >> 16;
+        for body_part_info in actor_body_part_infos.infos {
+            let Some(part_id) = body_part_info.part_id else {
+                continue;
+            };
+            let body_part = self
+                .entity_uid_to_parts
+                .entry(entity_uid)
+                .or_default()
+                .entry(part_id)
+                .or_default();
+            body_part.hp = body_part_info.hp.or(body_part.hp);
+            body_part.max_hp = body_part_info.max_hp.or(body_part.max_hp);
+            let Some(state) = body_part_info
+                .state
+                .and_then(|state| BodyPartState::try_from(state).ok())
+            else {
+                continue;
+            };
+            if state != body_part.state {
+                info!(
+                    "body part {part_id} of {entity_uid}: {} -> {}",
+                    body_part.state.as_str_name(),
+                    state.as_str_name()
+                );
+                body_part.state = state;
+                body_part.state_changes.push((timestamp_ms, state));
+            }
+        }
+    }
+
+    pub fn record_part_hits(
+        &mut self,
+        part_infos: &[blueprotobuf::ClientHitPartInfo],
+        target_uid: i64,
+        attacker_uid: i64,
+    ) {
+        for part_info in part_infos {
+            let (Some(part_id), Some(damage_val)) = (part_info.part_id, part_info.damage_val)
+            else {
+                continue;
+            };
+            let part_dmg = self
+                .entity_uid_to_parts
+                .entry(target_uid)
+                .or_default()
+                .entry(part_id)
+                .or_default()
+                .attacker_uid_to_dmg
+                .entry(attacker_uid)
+                .or_default();
+            part_dmg.value += damage_val;
+            part_dmg.hits += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::body_part_tracker::BodyPartTracker;
+    use blueprotobuf_lib::blueprotobuf::{
+        ActorBodyPartInfo, ActorBodyPartInfos, BodyPartState, ClientHitPartInfo,
+    };
+
+    const BOSS_UUID: i64 = (42 << 16) | 64;
+    const PART_ID: i32 = 3;
+    const PLAYER_UID: i64 = 7;
+
+    fn part_info(hp: Option<u64>, state: Option<BodyPartState>) -> ActorBodyPartInfos {
+        ActorBodyPartInfos {
+            uuid: None,
+            infos: vec![ActorBodyPartInfo {
+                part_id: Some(PART_ID),
+                hp,
+                max_hp: Some(1_000),
+                state: state.map(|state| state as i32),
+                fleshy_id: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_state_transitions() {
+        let mut body_part_tracker = BodyPartTracker::default();
+        body_part_tracker.process_body_part_infos(
+            part_info(Some(1_000), Some(BodyPartState::Default)),
+            BOSS_UUID,
+            1_000,
+        );
+        body_part_tracker.process_body_part_infos(
+            part_info(Some(400), Some(BodyPartState::Injury)),
+            BOSS_UUID,
+            2_000,
+        );
+        // Resent state and a delta without one don't count as changes, the HP still updates
+        body_part_tracker.process_body_part_infos(
+            part_info(Some(300), Some(BodyPartState::Injury)),
+            BOSS_UUID,
+            3_000,
+        );
+        body_part_tracker.process_body_part_infos(part_info(Some(200), None), BOSS_UUID, 4_000);
+
+        let body_part = &body_part_tracker.entity_uid_to_parts[&(BOSS_UUID >> 16)][&PART_ID];
+        assert_eq!(body_part.hp, Some(200));
+        assert_eq!(body_part.state, BodyPartState::Injury);
+        assert_eq!(
+            body_part.state_changes,
+            vec![(2_000, BodyPartState::Injury)]
+        );
+        assert_eq!(body_part.broken_ms(), None);
+    }
+
+    #[test]
+    fn test_broken_ms() {
+        let mut body_part_tracker = BodyPartTracker::default();
+        for (timestamp_ms, state) in [
+            (1_000, BodyPartState::Dead),
+            (5_000, BodyPartState::Default), // part regrows
+            (9_000, BodyPartState::Dead),
+        ] {
+            body_part_tracker.process_body_part_infos(
+                part_info(None, Some(state)),
+                BOSS_UUID,
+                timestamp_ms,
+            );
+        }
+
+        // Only the first break counts
+        let body_part = &body_part_tracker.entity_uid_to_parts[&(BOSS_UUID >> 16)][&PART_ID];
+        assert_eq!(body_part.state_changes.len(), 3);
+        assert_eq!(body_part.broken_ms(), Some(1_000));
+    }
+
+    #[test]
+    fn test_record_part_hits() {
+        let mut body_part_tracker = BodyPartTracker::default();
+        let part_hits = [
+            ClientHitPartInfo {
+                part_id: Some(PART_ID),
+                damage_pos: None,
+                damage_val: Some(300),
+            },
+            ClientHitPartInfo {
+                part_id: None,
+                damage_pos: None,
+                damage_val: Some(500),
+            },
+        ];
+        body_part_tracker.record_part_hits(&part_hits, BOSS_UUID >> 16, PLAYER_UID);
+        body_part_tracker.record_part_hits(&part_hits, BOSS_UUID >> 16, PLAYER_UID);
+
+        let part_dmg = &body_part_tracker.entity_uid_to_parts[&(BOSS_UUID >> 16)][&PART_ID]
+            .attacker_uid_to_dmg[&PLAYER_UID];
+        assert_eq!((part_dmg.value, part_dmg.hits), (600, 2));
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::{HashMap, VecDeque};
+
+// Default HP% thresholds that split a boss fight into phases
+pub const DEFAULT_PHASE_THRESHOLDS_PCT: [f64; 3] = [75.0, 50.0, 25.0];
+// How far back the group's hits on a boss are kept to estimate its DPS for the kill-time projection
+pub const KILL_PROJECTION_WINDOW_MS: u128 = 30_000;
+// How long an engaged boss that isn't dead has to go without HP changes or hits before it no longer holds the encounter open
+pub const BOSS_INACTIVE_GRACE_MS: u128 = 10_000;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HpSample {
+    pub timestamp_ms: u128,
+    pub curr_hp: i64,
+    pub max_hp: i64,
+}
+
+impl HpSample {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hp_pct(&self) -> f64 {
+        if self.max_hp <= 0 {
+            return 0.0;
+        }
+        self.curr_hp as f64 / self.max_hp as f64 * 100.0
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BossPhase {
+    pub index: usize,
+    pub start_threshold_pct: f64, // 100 for the first phase
+    pub start_ms: u128,
+    pub end_ms: Option<u128>, // None while the phase is still ongoing
+}
+
+/// Keeps a timestamped HP series for every boss-flagged monster
+#[derive(Debug, Default, Clone)]
+pub struct BossTracker {
+    pub boss_uid_to_hp_timeline: HashMap<i64, Vec<HpSample>>,
+    boss_uid_to_recent_dmg: HashMap<i64, VecDeque<(u128, i64)>>, // (timestamp_ms, value), oldest first
+    boss_uid_to_first_dmg_ms: HashMap<i64, u128>,
+    pub last_boss_death_ms: Option<u128>,
+}
+
+impl BossTracker {
+    pub fn record_hp(&mut self, boss_uid: i64, curr_hp: i32, max_hp: i32, timestamp_ms: u128) {
+        let timeline = self.boss_uid_to_hp_timeline.entry(boss_uid).or_default();
+        let sample = HpSample {
+            timestamp_ms,
+            curr_hp: i64::from(curr_hp),
+            max_hp: i64::from(max_hp),
+        };
+        // Only keep samples where something changed
+        if let Some(last) = timeline.last() {
+            if last.curr_hp == sample.curr_hp && last.max_hp == sample.max_hp {
+                return;
+            }
+            if last.curr_hp > 0 && sample.curr_hp <= 0 {
+                self.last_boss_death_ms = Some(timestamp_ms);
+            }
+        }
+        timeline.push(sample);
+    }
+
+    /// Damage dealt to the boss by anyone, only the last `KILL_PROJECTION_WINDOW_MS` are kept
+    pub fn record_dmg(&mut self, boss_uid: i64, value: i64, timestamp_ms: u128) {
+        self.boss_uid_to_first_dmg_ms
+            .entry(boss_uid)
+            .or_insert(timestamp_ms);
+        let recent_dmg = self.boss_uid_to_recent_dmg.entry(boss_uid).or_default();
+        recent_dmg.push_back((timestamp_ms, value));
+        let window_start_ms = timestamp_ms.saturating_sub(KILL_PROJECTION_WINDOW_MS);
+        while recent_dmg
+            .front()
+            .is_some_and(|&(hit_ms, _)| hit_ms < window_start_ms)
+        {
+            recent_dmg.pop_front();
+        }
+    }
+
+    /// Whether every boss the group engaged (hit or saw lose HP) is dead, or hasn't been touched for `grace_ms`
+    pub fn are_engaged_bosses_down(&self, now_ms: u128, grace_ms: u128) -> bool {
+        let engaged_boss_uids = self
+            .boss_uid_to_hp_timeline
+            .iter()
+            .filter(|(_, timeline)| timeline.iter().any(|sample| sample.curr_hp < sample.max_hp))
+            .map(|(boss_uid, _)| boss_uid)
+            .chain(self.boss_uid_to_first_dmg_ms.keys());
+        for boss_uid in engaged_boss_uids {
+            let last_sample = self
+                .boss_uid_to_hp_timeline
+                .get(boss_uid)
+                .and_then(|timeline| timeline.last());
+            if last_sample.is_some_and(|sample| sample.curr_hp <= 0) {
+                continue;
+            }
+            let last_hit_ms = self
+                .boss_uid_to_recent_dmg
+                .get(boss_uid)
+                .and_then(|recent_dmg| recent_dmg.back())
+                .map(|&(hit_ms, _)| hit_ms);
+            let last_active_ms = last_sample
+                .map(|sample| sample.timestamp_ms)
+                .max(last_hit_ms)
+                .unwrap_or_default();
+            if now_ms.saturating_sub(last_active_ms) <= grace_ms {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn get_timeline(&self, boss_uid: i64) -> &[HpSample] {
+        self.boss_uid_to_hp_timeline
+            .get(&boss_uid)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Splits the HP timeline into phases at the first sample that drops to or below each threshold
+    pub fn get_phases(&self, boss_uid: i64, phase_thresholds_pct: &[f64]) -> Vec<BossPhase> {
+        let timeline = self.get_timeline(boss_uid);
+        let Some(first_sample) = timeline.first() else {
+            return Vec::new();
+        };
+
+        let mut thresholds_pct: Vec<f64> = phase_thresholds_pct
+            .iter()
+            .copied()
+            .filter(|threshold_pct| *threshold_pct > 0.0 && *threshold_pct < 100.0)
+            .collect();
+        thresholds_pct.sort_by(|a, b| b.total_cmp(a)); // highest first
+
+        let mut phases = vec![BossPhase {
+            index: 0,
+            start_threshold_pct: 100.0,
+            start_ms: first_sample.timestamp_ms,
+            end_ms: None,
+        }];
+        let mut thresholds_pct = thresholds_pct.into_iter().peekable();
+        for sample in timeline {
+            let hp_pct = sample.hp_pct();
+            // A single big hit can skip several thresholds, those phases end up with zero length
+            while let Some(threshold_pct) = thresholds_pct.next_if(|&t| hp_pct <= t) {
+                if let Some(prev_phase) = phases.last_mut() {
+                    prev_phase.end_ms = Some(sample.timestamp_ms);
+                }
+                phases.push(BossPhase {
+                    index: phases.len(),
+                    start_threshold_pct: threshold_pct,
+                    start_ms: sample.timestamp_ms,
+                    end_ms: None,
+                });
+            }
+        }
+
+        // A dead boss closes the last phase
+        if let Some(last_sample) = timeline.last().filter(|sample| sample.curr_hp <= 0) {
+            if let Some(last_phase) = phases.last_mut() {
+                last_phase.end_ms = Some(last_sample.timestamp_ms);
+            }
+        }
+        phases
+    }
+
+    /// Group DPS against the boss over the last `window_ms` (capped at `KILL_PROJECTION_WINDOW_MS`) before `now_ms`,
+    /// or since the boss was first hit if that's more recent. Heals and shields on the boss don't drag it down the way HP loss would.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_recent_dps(&self, boss_uid: i64, window_ms: u128, now_ms: u128) -> f64 {
+        let (Some(recent_dmg), Some(&first_dmg_ms)) = (
+            self.boss_uid_to_recent_dmg.get(&boss_uid),
+            self.boss_uid_to_first_dmg_ms.get(&boss_uid),
+        ) else {
+            return 0.0;
+        };
+        let window_ms = window_ms.min(KILL_PROJECTION_WINDOW_MS);
+        let window_start_ms = now_ms.saturating_sub(window_ms);
+        let dmg: i64 = recent_dmg
+            .iter()
+            .filter(|&&(hit_ms, _)| hit_ms >= window_start_ms && hit_ms <= now_ms)
+            .map(|&(_, value)| value)
+            .sum();
+
+        let elapsed_ms = window_ms.min(now_ms.saturating_sub(first_dmg_ms));
+        if elapsed_ms == 0 || dmg <= 0 {
+            return 0.0;
+        }
+        dmg as f64 / (elapsed_ms as f64 / 1000.0)
+    }
+
+    /// Time left until the boss dies at the recent group DPS, None if it can't be estimated
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn get_projected_kill_ms(
+        &self,
+        boss_uid: i64,
+        window_ms: u128,
+        now_ms: u128,
+    ) -> Option<u128> {
+        let last_sample = self.get_timeline(boss_uid).last()?;
+        if last_sample.curr_hp <= 0 {
+            return Some(0);
+        }
+        let recent_dps = self.get_recent_dps(boss_uid, window_ms, now_ms);
+        if recent_dps <= 0.0 {
+            return None;
+        }
+        Some((last_sample.curr_hp as f64 / recent_dps * 1000.0) as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::boss_tracker::{
+        BOSS_INACTIVE_GRACE_MS, BossTracker, DEFAULT_PHASE_THRESHOLDS_PCT,
+    };
+
+    const BOSS_UID: i64 = 42;
+
+    #[test]
+    fn test_get_phases() {
+        let mut boss_tracker = BossTracker::default();
+        boss_tracker.record_hp(BOSS_UID, 1000, 1000, 0);
+        boss_tracker.record_hp(BOSS_UID, 800, 1000, 1_000);
+        boss_tracker.record_hp(BOSS_UID, 700, 1000, 2_000); // crosses 75%
+        boss_tracker.record_hp(BOSS_UID, 200, 1000, 3_000); // skips 50% and crosses 25%
+        boss_tracker.record_hp(BOSS_UID, 0, 1000, 4_000);
+
+        let phases = boss_tracker.get_phases(BOSS_UID, &DEFAULT_PHASE_THRESHOLDS_PCT);
+        let phase_spans: Vec<(f64, u128, Option<u128>)> = phases
+            .iter()
+            .map(|phase| (phase.start_threshold_pct, phase.start_ms, phase.end_ms))
+            .collect();
+        assert_eq!(
+            phase_spans,
+            vec![
+                (100.0, 0, Some(2_000)),
+                (75.0, 2_000, Some(3_000)),
+                (50.0, 3_000, Some(3_000)),
+                (25.0, 3_000, Some(4_000)),
+            ]
+        );
+        assert_eq!(boss_tracker.last_boss_death_ms, Some(4_000));
+    }
+
+    #[test]
+    fn test_get_phases_ongoing() {
+        let mut boss_tracker = BossTracker::default();
+        boss_tracker.record_hp(BOSS_UID, 1000, 1000, 0);
+        boss_tracker.record_hp(BOSS_UID, 600, 1000, 1_000);
+
+        // Out of range thresholds are ignored
+        let phases = boss_tracker.get_phases(BOSS_UID, &[0.0, 75.0, 150.0]);
+        assert_eq!(phases.len(), 2);
+        assert_eq!(phases[0].end_ms, Some(1_000));
+        assert_eq!(phases[1].start_threshold_pct, 75.0);
+        assert_eq!(phases[1].end_ms, None);
+        assert!(
+            boss_tracker
+                .get_phases(7, &DEFAULT_PHASE_THRESHOLDS_PCT)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_get_projected_kill_ms() {
+        let mut boss_tracker = BossTracker::default();
+        boss_tracker.record_hp(BOSS_UID, 10_000, 10_000, 0);
+        assert_eq!(
+            boss_tracker.get_projected_kill_ms(BOSS_UID, 30_000, 0),
+            None
+        );
+
+        // The group hit for 5000 but shields/heals kept the HP loss at 2000, the projection follows the damage
+        for second in 0..=4 {
+            boss_tracker.record_dmg(BOSS_UID, 1_000, second * 1_000);
+        }
+        boss_tracker.record_hp(BOSS_UID, 8_000, 10_000, 4_000);
+        assert!(
+            (boss_tracker.get_recent_dps(BOSS_UID, 30_000, 4_000) - 1_250.0).abs() < f64::EPSILON
+        );
+        assert_eq!(
+            boss_tracker.get_projected_kill_ms(BOSS_UID, 30_000, 4_000),
+            Some(6_400)
+        );
+
+        // Only the window counts
+        assert!(
+            (boss_tracker.get_recent_dps(BOSS_UID, 2_000, 4_000) - 1_500.0).abs() < f64::EPSILON
+        );
+
+        boss_tracker.record_hp(BOSS_UID, 0, 10_000, 5_000);
+        assert_eq!(
+            boss_tracker.get_projected_kill_ms(BOSS_UID, 30_000, 5_000),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_record_dmg_drops_old_hits() {
+        let mut boss_tracker = BossTracker::default();
+        boss_tracker.record_dmg(BOSS_UID, 1_000_000, 0);
+        boss_tracker.record_dmg(BOSS_UID, 500, 40_000);
+        boss_tracker.record_dmg(BOSS_UID, 500, 41_000);
+        // The window is capped to the hits that are kept
+        assert!(
+            (boss_tracker.get_recent_dps(BOSS_UID, 60_000, 41_000) - 1_000.0 / 30.0).abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_recent_dps_burst() {
+        let mut boss_tracker = BossTracker::default();
+        boss_tracker.record_dmg(BOSS_UID, 1_000, 0);
+        // A burst of hits close together is spread over the whole window, not its own 100ms
+        boss_tracker.record_dmg(BOSS_UID, 1_000, 40_000);
+        boss_tracker.record_dmg(BOSS_UID, 1_000, 40_050);
+        boss_tracker.record_dmg(BOSS_UID, 1_000, 40_100);
+        assert!(
+            (boss_tracker.get_recent_dps(BOSS_UID, 30_000, 40_100) - 100.0).abs() < f64::EPSILON
+        );
+
+        // Falls off once the group stops hitting
+        assert!((boss_tracker.get_recent_dps(BOSS_UID, 30_000, 80_000)).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_engaged_bosses_down() {
+        const ADD_UID: i64 = 43;
+        const IDLE_UID: i64 = 44;
+        let mut boss_tracker = BossTracker::default();
+        boss_tracker.record_hp(BOSS_UID, 1000, 1000, 0);
+        boss_tracker.record_hp(ADD_UID, 1000, 1000, 0);
+        boss_tracker.record_hp(IDLE_UID, 1000, 1000, 0); // never engaged
+        boss_tracker.record_dmg(BOSS_UID, 500, 1_000);
+        boss_tracker.record_hp(BOSS_UID, 500, 1000, 1_000);
+        boss_tracker.record_dmg(ADD_UID, 500, 2_000);
+        boss_tracker.record_hp(ADD_UID, 500, 1000, 2_000);
+
+        boss_tracker.record_hp(BOSS_UID, 0, 1000, 3_000);
+        assert_eq!(boss_tracker.last_boss_death_ms, Some(3_000));
+        assert!(!boss_tracker.are_engaged_bosses_down(3_000, BOSS_INACTIVE_GRACE_MS));
+
+        // The add left alive stops holding the encounter open once it's left alone
+        assert!(
+            !boss_tracker
+                .are_engaged_bosses_down(2_000 + BOSS_INACTIVE_GRACE_MS, BOSS_INACTIVE_GRACE_MS)
+        );
+        assert!(
+            boss_tracker
+                .are_engaged_bosses_down(2_001 + BOSS_INACTIVE_GRACE_MS, BOSS_INACTIVE_GRACE_MS)
+        );
+
+        boss_tracker.record_hp(ADD_UID, 0, 1000, 4_000);
+        assert!(boss_tracker.are_engaged_bosses_down(4_000, BOSS_INACTIVE_GRACE_MS));
+    }
+}
@@ -3,7 +3,6 @@ use log::{error, info};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 struct CacheEntry {
@@ -38,6 +37,7 @@ impl BPTimerClient {
         pos_z: Option<f32>,
         account_id: Option<String>,
         uid: Option<i64>,
+        timestamp_ms: u128,
     ) {
         // Validate all required fields are present
         let Some(monster_id) = monster_id else {
@@ -78,10 +78,6 @@ impl BPTimerClient {
         let rounded_hp_pct = rounded_hp_pct.clamp(0, 100);
 
         let cache_key = format!("{}-{}", monster_id, line as i32);
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis();
 
         // Check cache
         let should_report = {
@@ -89,14 +85,14 @@ impl BPTimerClient {
             let entry = cache
                 .entry(cache_key.clone())
                 .or_insert_with(|| CacheEntry {
-                    timestamp: now,
+                    timestamp: timestamp_ms,
                     last_reported_hp: None,
                     is_pending: false,
                 });
 
             // Reset expired entries
-            if now - entry.timestamp > CACHE_EXPIRY_MS {
-                entry.timestamp = now;
+            if timestamp_ms.saturating_sub(entry.timestamp) > CACHE_EXPIRY_MS {
+                entry.timestamp = timestamp_ms;
                 entry.last_reported_hp = None;
                 entry.is_pending = false;
             }
@@ -0,0 +1,484 @@
+use crate::live::event_log::{BuffEvent, EventLog};
+use blueprotobuf_lib::blueprotobuf;
+use log::debug;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BuffTableEntry {
+    chinese_short: Option<String>,
+    english_short: Option<String>,
+}
+
+static BUFF_NAMES: Lazy<HashMap<i32, BuffTableEntry>> = Lazy::new(|| {
+    let data = include_str!("../../../raw-game-files/2_Clean/BuffTable_Clean.json");
+    serde_json::from_str(data).expect("invalid BuffTable_Clean.json")
+});
+
+pub fn get_buff_name(base_id: i32) -> String {
+    BUFF_NAMES
+        .get(&base_id)
+        .and_then(|entry| entry.english_short.clone().or(entry.chinese_short.clone()))
+        .unwrap_or_else(|| format!("UNKNOWN BUFF ({base_id})"))
+}
+
+/// Tracks buff instances per host entity and accumulates uptime per buff type (`base_id`)
+#[derive(Debug, Default, Clone)]
+pub struct BuffTracker {
+    // host uid => buff_uuid => buff instance currently on the host
+    active_buffs: HashMap<i64, HashMap<i32, ActiveBuff>>,
+    pub host_uid_to_buff_stats: HashMap<i64, HashMap<i32, BuffStats>>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct ActiveBuff {
+    base_id: i32,
+    layer: i32,
+    create_time: Option<i64>,
+    duration_ms: Option<u128>,
+    last_refresh_ms: u128,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BuffStats {
+    pub applications: i64,
+    pub refreshes: i64,
+    pub removals: i64,
+    pub max_layer: i32,
+    pub curr_layer: i32,
+    pub source_uid_to_applications: HashMap<i64, i64>,
+    // (start, end) of each period where at least one instance was up, end is None while still up
+    pub uptime_intervals: Vec<(u128, Option<u128>)>,
+    active_instances: u32,
+}
+
+impl BuffStats {
+    fn on_instance_up(&mut self, timestamp_ms: u128) {
+        if self.active_instances == 0 {
+            self.uptime_intervals.push((timestamp_ms, None));
+        }
+        self.active_instances += 1;
+    }
+
+    fn on_instance_down(&mut self, timestamp_ms: u128) {
+        self.active_instances = self.active_instances.saturating_sub(1);
+        if self.active_instances == 0 {
+            if let Some((_, end @ None)) = self.uptime_intervals.last_mut() {
+                *end = Some(timestamp_ms);
+            }
+            self.curr_layer = 0;
+        }
+    }
+
+    /// Time the buff was up inside `[window_start_ms, window_end_ms]`, the open period ends at `expires_ms` if known
+    fn uptime_ms(
+        &self,
+        window_start_ms: u128,
+        window_end_ms: u128,
+        expires_ms: Option<u128>,
+    ) -> u128 {
+        self.uptime_intervals
+            .iter()
+            .map(|&(start, end)| {
+                let start = start.max(window_start_ms);
+                let end = end
+                    .or(expires_ms)
+                    .unwrap_or(window_end_ms)
+                    .min(window_end_ms);
+                end.saturating_sub(start)
+            })
+            .sum()
+    }
+}
+
+impl BuffTracker {
+    /// Buffs still up when an encounter closes, so they count toward the next encounter's uptime
+    pub fn carry_over(&self) -> Self {
+        let mut host_uid_to_buff_stats: HashMap<i64, HashMap<i32, BuffStats>> = HashMap::new();
+        for (&host_uid, base_id_to_buff_stats) in &self.host_uid_to_buff_stats {
+            for (&base_id, buff_stats) in base_id_to_buff_stats {
+                if buff_stats.active_instances == 0 {
+                    continue;
+                }
+                host_uid_to_buff_stats.entry(host_uid).or_default().insert(
+                    base_id,
+                    BuffStats {
+                        max_layer: buff_stats.curr_layer,
+                        curr_layer: buff_stats.curr_layer,
+                        uptime_intervals: buff_stats
+                            .uptime_intervals
+                            .last()
+                            .copied()
+                            .into_iter()
+                            .collect(),
+                        active_instances: buff_stats.active_instances,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+        Self {
+            active_buffs: self.active_buffs.clone(),
+            host_uid_to_buff_stats,
+        }
+    }
+
+    /// Expiry of the latest `base_id` instance on the host, None if one of them has no duration
+    fn expires_ms(&self, host_uid: i64, base_id: i32) -> Option<u128> {
+        self.active_buffs
+            .get(&host_uid)?
+            .values()
+            .filter(|active_buff| active_buff.base_id == base_id)
+            .map(|active_buff| {
+                active_buff
+                    .duration_ms
+                    .map(|duration_ms| active_buff.last_refresh_ms + duration_ms)
+            })
+            .collect::<Option<Vec<u128>>>()?
+            .into_iter()
+            .max()
+    }
+
+    /// Time `base_id` was up on the host inside the window, buffs that ran out without a remove event stop counting at their expiry
+    pub fn get_uptime_ms(
+        &self,
+        host_uid: i64,
+        base_id: i32,
+        window_start_ms: u128,
+        window_end_ms: u128,
+    ) -> u128 {
+        self.host_uid_to_buff_stats
+            .get(&host_uid)
+            .and_then(|base_id_to_buff_stats| base_id_to_buff_stats.get(&base_id))
+            .map_or(0, |buff_stats| {
+                buff_stats.uptime_ms(
+                    window_start_ms,
+                    window_end_ms,
+                    self.expires_ms(host_uid, base_id),
+                )
+            })
+    }
+
+    pub fn is_active(&self, host_uid: i64, base_id: i32, timestamp_ms: u128) -> bool {
+        let is_up = self
+            .host_uid_to_buff_stats
+            .get(&host_uid)
+            .and_then(|base_id_to_buff_stats| base_id_to_buff_stats.get(&base_id))
+            .is_some_and(|buff_stats| buff_stats.active_instances > 0);
+        is_up
+            && self
+                .expires_ms(host_uid, base_id)
+                .is_none_or(|expires_ms| expires_ms >= timestamp_ms)
+    }
+
+    pub fn process_buff_infos(
+        &mut self,
+        buff_info_sync: blueprotobuf::BuffInfoSync,
+        default_host_uuid: i64,
+        timestamp_ms: u128,
+        event_log: &mut EventLog,
+    ) {
+        let default_host_uuid = buff_info_sync.uuid.unwrap_or(default_host_uuid);
+        for buff_info in buff_info_sync.buff_infos {
+            let (Some(buff_uuid), Some(base_id)) = (buff_info.buff_uuid, buff_info.base_id) else {
+                continue;
+            };
+            let host_uid = buff_info.host_uuid.unwrap_or(default_host_uuid) >> 16;
+            self.expire_host_buffs(host_uid, timestamp_ms, event_log);
+            let layer = buff_info.layer.unwrap_or(1);
+            let duration_ms = buff_info
+                .duration
+                .filter(|&duration| duration > 0)
+                .map(|duration| duration as u128);
+
+            let host_buffs = self.active_buffs.entry(host_uid).or_default();
+            let buff_stats = self
+                .host_uid_to_buff_stats
+                .entry(host_uid)
+                .or_default()
+                .entry(base_id)
+                .or_default();
+
+            match host_buffs.get_mut(&buff_uuid) {
+                // Already applied, only count it as a refresh if something actually changed
+                Some(active_buff) => {
+                    if active_buff.create_time != buff_info.create_time
+                        || active_buff.layer != layer
+                        || active_buff.duration_ms != duration_ms
+                    {
+                        active_buff.create_time = buff_info.create_time;
+                        active_buff.layer = layer;
+                        active_buff.duration_ms = duration_ms;
+                        active_buff.last_refresh_ms = timestamp_ms;
+                        buff_stats.refreshes += 1;
+                    }
+                }
+                None => {
+                    host_buffs.insert(
+                        buff_uuid,
+                        ActiveBuff {
+                            base_id,
+                            layer,
+                            create_time: buff_info.create_time,
+                            duration_ms,
+                            last_refresh_ms: timestamp_ms,
+                        },
+                    );
+                    buff_stats.applications += 1;
+                    if let Some(fire_uuid) = buff_info.fire_uuid {
+                        *buff_stats
+                            .source_uid_to_applications
+                            .entry(fire_uuid >> 16)
+                            .or_default() += 1;
+                    }
+                    buff_stats.on_instance_up(timestamp_ms);
+                    event_log.record_buff(BuffEvent {
+                        timestamp_ms,
+                        host_uid,
+                        source_uid: buff_info.fire_uuid.map(|fire_uuid| fire_uuid >> 16),
+                        base_id,
+                        layer,
+                        is_gained: true,
+                    });
+                    debug!("buff {base_id} applied to {host_uid}");
+                }
+            }
+            buff_stats.curr_layer = layer;
+            buff_stats.max_layer = buff_stats.max_layer.max(layer);
+        }
+    }
+
+    pub fn process_buff_effects(
+        &mut self,
+        buff_effect_sync: blueprotobuf::BuffEffectSync,
+        default_host_uuid: i64,
+        timestamp_ms: u128,
+        event_log: &mut EventLog,
+    ) {
+        let default_host_uuid = buff_effect_sync.uuid.unwrap_or(default_host_uuid);
+        for buff_effect in buff_effect_sync.buff_effects {
+            let Some(buff_uuid) = buff_effect.buff_uuid else {
+                continue;
+            };
+            let host_uid = buff_effect.host_uuid.unwrap_or(default_host_uuid) >> 16;
+            self.expire_host_buffs(host_uid, timestamp_ms, event_log);
+            let event_type = buff_effect.r#type.unwrap_or_default();
+
+            if event_type == blueprotobuf::EBuffEventType::BuffEventRemove as i32 {
+                self.remove_buff(host_uid, buff_uuid, timestamp_ms, event_log);
+            } else if event_type == blueprotobuf::EBuffEventType::BuffEventReplace as i32
+                || event_type == blueprotobuf::EBuffEventType::BuffEventStackLayer as i32
+            {
+                // AddTo is ignored, the matching BuffInfo carries the base_id we need
+                let Some(active_buff) = self
+                    .active_buffs
+                    .get_mut(&host_uid)
+                    .and_then(|host_buffs| host_buffs.get_mut(&buff_uuid))
+                else {
+                    continue;
+                };
+                active_buff.last_refresh_ms = timestamp_ms;
+                if let Some(buff_stats) = self
+                    .host_uid_to_buff_stats
+                    .get_mut(&host_uid)
+                    .and_then(|buff_stats| buff_stats.get_mut(&active_buff.base_id))
+                {
+                    buff_stats.refreshes += 1;
+                }
+            }
+        }
+    }
+
+    /// Closes the host's buffs whose duration ran out without us seeing a remove event (e.g. host left view range).
+    /// Only done when the host's buffs change, reads account for expiry on their own
+    fn expire_host_buffs(&mut self, host_uid: i64, timestamp_ms: u128, event_log: &mut EventLog) {
+        let Some(host_buffs) = self.active_buffs.get(&host_uid) else {
+            return;
+        };
+        let expired: Vec<(i32, u128)> = host_buffs
+            .iter()
+            .filter_map(|(&buff_uuid, active_buff)| {
+                let expires_ms = active_buff.last_refresh_ms + active_buff.duration_ms?;
+                (expires_ms < timestamp_ms).then_some((buff_uuid, expires_ms))
+            })
+            .collect();
+        for (buff_uuid, expires_ms) in expired {
+            self.remove_buff(host_uid, buff_uuid, expires_ms, event_log);
+        }
+    }
+
+    fn remove_buff(
+        &mut self,
+        host_uid: i64,
+        buff_uuid: i32,
+        timestamp_ms: u128,
+        event_log: &mut EventLog,
+    ) {
+        let Some(active_buff) = self
+            .active_buffs
+            .get_mut(&host_uid)
+            .and_then(|host_buffs| host_buffs.remove(&buff_uuid))
+        else {
+            return;
+        };
+        if let Some(buff_stats) = self
+            .host_uid_to_buff_stats
+            .get_mut(&host_uid)
+            .and_then(|buff_stats| buff_stats.get_mut(&active_buff.base_id))
+        {
+            buff_stats.removals += 1;
+            buff_stats.on_instance_down(timestamp_ms);
+        }
+        event_log.record_buff(BuffEvent {
+            timestamp_ms,
+            host_uid,
+            source_uid: None,
+            base_id: active_buff.base_id,
+            layer: active_buff.layer,
+            is_gained: false,
+        });
+        debug!("buff {} removed from {host_uid}", active_buff.base_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::buff_tracker::BuffTracker;
+    use crate::live::event_log::EventLog;
+    use blueprotobuf_lib::blueprotobuf::{
+        BuffEffect, BuffEffectSync, BuffInfo, BuffInfoSync, EBuffEventType,
+    };
+
+    const HOST_UUID: i64 = (1234 << 16) | 640;
+    const HOST_UID: i64 = 1234;
+    const BUFF_UUID: i32 = 7;
+    const BASE_ID: i32 = 2110;
+
+    fn apply(
+        buff_tracker: &mut BuffTracker,
+        duration: Option<i32>,
+        create_time: i64,
+        timestamp_ms: u128,
+    ) {
+        buff_tracker.process_buff_infos(
+            BuffInfoSync {
+                uuid: Some(HOST_UUID),
+                buff_infos: vec![BuffInfo {
+                    buff_uuid: Some(BUFF_UUID),
+                    base_id: Some(BASE_ID),
+                    layer: Some(1),
+                    duration,
+                    create_time: Some(create_time),
+                    fire_uuid: Some(HOST_UUID),
+                    ..Default::default()
+                }],
+            },
+            0,
+            timestamp_ms,
+            &mut EventLog::default(),
+        );
+    }
+
+    fn remove(buff_tracker: &mut BuffTracker, timestamp_ms: u128) {
+        buff_tracker.process_buff_effects(
+            BuffEffectSync {
+                uuid: Some(HOST_UUID),
+                buff_effects: vec![BuffEffect {
+                    r#type: Some(EBuffEventType::BuffEventRemove as i32),
+                    buff_uuid: Some(BUFF_UUID),
+                    ..Default::default()
+                }],
+            },
+            0,
+            timestamp_ms,
+            &mut EventLog::default(),
+        );
+    }
+
+    #[test]
+    fn test_apply_refresh_remove() {
+        let mut buff_tracker = BuffTracker::default();
+        apply(&mut buff_tracker, None, 1, 1_000);
+        apply(&mut buff_tracker, None, 1, 2_000); // resent unchanged
+        apply(&mut buff_tracker, None, 2, 3_000); // recast
+        remove(&mut buff_tracker, 4_000);
+        remove(&mut buff_tracker, 5_000); // already gone
+
+        let buff_stats = &buff_tracker.host_uid_to_buff_stats[&HOST_UID][&BASE_ID];
+        assert_eq!(buff_stats.applications, 1);
+        assert_eq!(buff_stats.refreshes, 1);
+        assert_eq!(buff_stats.removals, 1);
+        assert_eq!(buff_stats.source_uid_to_applications[&HOST_UID], 1);
+        assert_eq!(buff_stats.uptime_intervals, vec![(1_000, Some(4_000))]);
+        assert!(!buff_tracker.is_active(HOST_UID, BASE_ID, 4_000));
+    }
+
+    #[test]
+    fn test_uptime_clipped_to_window() {
+        let mut buff_tracker = BuffTracker::default();
+        apply(&mut buff_tracker, None, 1, 1_000);
+        remove(&mut buff_tracker, 4_000);
+        apply(&mut buff_tracker, None, 2, 6_000);
+
+        assert_eq!(
+            buff_tracker.get_uptime_ms(HOST_UID, BASE_ID, 2_000, 8_000),
+            4_000
+        );
+        assert_eq!(
+            buff_tracker.get_uptime_ms(HOST_UID, BASE_ID, 0, 10_000),
+            7_000
+        );
+        assert!(buff_tracker.is_active(HOST_UID, BASE_ID, 10_000));
+    }
+
+    #[test]
+    fn test_expiry_without_remove_event() {
+        let mut buff_tracker = BuffTracker::default();
+        apply(&mut buff_tracker, Some(3_000), 1, 1_000);
+
+        // Read before anything touched the host again: stops counting at expiry
+        assert_eq!(
+            buff_tracker.get_uptime_ms(HOST_UID, BASE_ID, 0, 10_000),
+            3_000
+        );
+        assert!(buff_tracker.is_active(HOST_UID, BASE_ID, 4_000));
+        assert!(!buff_tracker.is_active(HOST_UID, BASE_ID, 4_001));
+
+        // The next buff change on the host closes it at its expiry
+        apply(&mut buff_tracker, Some(3_000), 2, 9_000);
+        let buff_stats = &buff_tracker.host_uid_to_buff_stats[&HOST_UID][&BASE_ID];
+        assert_eq!(buff_stats.removals, 1);
+        assert_eq!(buff_stats.uptime_intervals[0], (1_000, Some(4_000)));
+        assert_eq!(buff_stats.uptime_intervals[1], (9_000, None));
+    }
+
+    #[test]
+    fn test_active_buffs_carry_over() {
+        let mut buff_tracker = BuffTracker::default();
+        apply(&mut buff_tracker, None, 1, 1_000);
+        let mut next_buff_tracker = buff_tracker.carry_over();
+
+        // Up for the whole of the next encounter, and its removal still closes it
+        assert_eq!(
+            next_buff_tracker.get_uptime_ms(HOST_UID, BASE_ID, 5_000, 8_000),
+            3_000
+        );
+        remove(&mut next_buff_tracker, 7_000);
+        let buff_stats = &next_buff_tracker.host_uid_to_buff_stats[&HOST_UID][&BASE_ID];
+        assert_eq!(buff_stats.applications, 0);
+        assert_eq!(buff_stats.removals, 1);
+        assert_eq!(
+            next_buff_tracker.get_uptime_ms(HOST_UID, BASE_ID, 5_000, 8_000),
+            2_000
+        );
+
+        // Removed buffs don't carry over
+        assert!(
+            next_buff_tracker
+                .carry_over()
+                .host_uid_to_buff_stats
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::live::opcodes_models::class::{self, Class};
+use blueprotobuf_lib::blueprotobuf;
+
+/// The local player's build as of the last `SyncContainerData`, flattened out of `CharSerialize`.
+/// Dirty data isn't applied, so changes made since that snapshot aren't reflected
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterProfile {
+    pub uid: i64,
+    #[serde(default)]
+    pub snapshot_ms: Option<u128>, // when that SyncContainerData arrived
+    pub name: Option<String>,
+    pub level: Option<i32>,
+    pub ability_score: Option<i32>,
+    pub profession_id: Option<i32>,
+    pub class_name: String,
+    pub assist_profession_ids: Vec<i32>,
+    pub cur_hp: Option<i64>,
+    pub max_hp: Option<i64>,
+    pub equips: Vec<EquipProfile>,           // by slot
+    pub professions: Vec<ProfessionProfile>, // by profession_id
+    pub skill_slots: Vec<SkillSlotProfile>,  // by slot_id
+    pub mods: Vec<ModProfile>,               // by slot
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EquipProfile {
+    pub slot: i32,
+    pub item_uuid: Option<u64>,
+    pub refine_level: u32,
+    pub enchant_item_type_id: Option<i32>,
+    pub enchant_level: Option<i32>, // None when nothing is enchanted
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfessionProfile {
+    pub profession_id: i32,
+    pub level: Option<i32>,
+    pub active_skill_ids: Vec<i32>,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillSlotProfile {
+    pub slot_id: i32,
+    pub skill_id: Option<i32>,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModProfile {
+    pub slot: i32,
+    pub mod_uuid: i64,
+    pub part_ids: Vec<i32>,
+    pub success_rate: Option<i32>,
+}
+
+impl CharacterProfile {
+    pub fn from_char_serialize(
+        char_serialize: &blueprotobuf::CharSerialize,
+        snapshot_ms: Option<u128>,
+    ) -> Option<Self> {
+        let uid = char_serialize.char_id?;
+        let char_base = char_serialize.char_base.as_ref();
+        let profession_list = char_serialize.profession_list.as_ref();
+        let profession_id =
+            profession_list.and_then(|profession_list| profession_list.cur_profession_id);
+        let attr = char_serialize.attr.as_ref();
+        Some(Self {
+            uid,
+            snapshot_ms,
+            name: char_base.and_then(|char_base| char_base.name.clone()),
+            level: char_serialize
+                .role_level
+                .as_ref()
+                .and_then(|role_level| role_level.level),
+            ability_score: char_base.and_then(|char_base| char_base.fight_point),
+            profession_id,
+            class_name: class::get_class_name(profession_id.map_or(Class::Unknown, Class::from)),
+            assist_profession_ids: profession_list
+                .map(|profession_list| profession_list.cur_assist_professions.clone())
+                .unwrap_or_default(),
+            cur_hp: attr.and_then(|attr| attr.cur_hp),
+            max_hp: attr.and_then(|attr| attr.max_hp),
+            equips: char_serialize
+                .equip
+                .as_ref()
+                .map(get_equips)
+                .unwrap_or_default(),
+            professions: profession_list.map(get_professions).unwrap_or_default(),
+            skill_slots: char_serialize
+                .slots
+                .as_ref()
+                .map(get_skill_slots)
+                .unwrap_or_default(),
+            mods: char_serialize
+                .r#mod
+                .as_ref()
+                .map(get_mods)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+fn get_equips(equip_list: &blueprotobuf::EquipList) -> Vec<EquipProfile> {
+    let mut equips: Vec<EquipProfile> = equip_list
+        .equip_list
+        .iter()
+        .map(|(&slot, equip_info)| {
+            // Enchants are keyed by the enchanted item's uuid
+            let enchant = equip_info
+                .item_uuid
+                .and_then(|item_uuid| i64::try_from(item_uuid).ok())
+                .and_then(|item_uuid| equip_list.equip_enchant.get(&item_uuid));
+            EquipProfile {
+                slot: equip_info.equip_slot.unwrap_or(slot),
+                item_uuid: equip_info.item_uuid,
+                refine_level: equip_info.equip_slot_refine_level.unwrap_or_default(),
+                enchant_item_type_id: enchant.and_then(|enchant| enchant.enchant_item_type_id),
+                enchant_level: enchant.and_then(|enchant| enchant.enchant_level),
+            }
+        })
+        .collect();
+    equips.sort_by_key(|equip| equip.slot);
+    equips
+}
+
+fn get_professions(profession_list: &blueprotobuf::ProfessionList) -> Vec<ProfessionProfile> {
+    let mut professions: Vec<ProfessionProfile> = profession_list
+        .profession_list
+        .iter()
+        .map(|(&profession_id, profession_info)| ProfessionProfile {
+            profession_id: profession_info.profession_id.unwrap_or(profession_id),
+            level: profession_info.level,
+            active_skill_ids: profession_info.active_skill_ids.clone(),
+        })
+        .collect();
+    professions.sort_by_key(|profession| profession.profession_id);
+    professions
+}
+
+fn get_skill_slots(slots: &blueprotobuf::Slot) -> Vec<SkillSlotProfile> {
+    let mut skill_slots: Vec<SkillSlotProfile> = slots
+        .slots
+        .iter()
+        .map(|(&slot_id, slot_info)| SkillSlotProfile {
+            slot_id: slot_info.id.unwrap_or(slot_id),
+            skill_id: slot_info.skill_id,
+        })
+        .collect();
+    skill_slots.sort_by_key(|skill_slot| skill_slot.slot_id);
+    skill_slots
+}
+
+fn get_mods(r#mod: &blueprotobuf::Mod) -> Vec<ModProfile> {
+    let mut mods: Vec<ModProfile> = r#mod
+        .mod_slots
+        .iter()
+        .map(|(&slot, &mod_uuid)| {
+            let mod_info = r#mod.mod_infos.get(&mod_uuid);
+            ModProfile {
+                slot,
+                mod_uuid,
+                part_ids: mod_info
+                    .map(|mod_info| mod_info.part_ids.clone())
+                    .unwrap_or_default(),
+                success_rate: mod_info.and_then(|mod_info| mod_info.success_rate),
+            }
+        })
+        .collect();
+    mods.sort_by_key(|mod_profile| mod_profile.slot);
+    mods
+}
@@ -0,0 +1,69 @@
+use crate::live::opcodes_models::class::ClassSpec;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+// Bumped when the table format changes, newer override files are ignored
+const CLASS_SPEC_TABLE_VERSION: u32 = 1;
+
+// Optional file in the app data dir, its entries win over the bundled table
+pub const CLASS_SPEC_OVERRIDE_FILE: &str = "SkillClassSpec.json";
+
+// Hits from spec-exclusive skills needed before a player's spec is inferred
+pub const CLASS_SPEC_MIN_HITS: u32 = 3;
+
+#[derive(serde::Deserialize)]
+struct ClassSpecTable {
+    version: u32,
+    skills: HashMap<i32, ClassSpec>, // skill_id => spec, only skills exclusive to one spec
+}
+
+static CLASS_SPEC_TABLE: Lazy<RwLock<HashMap<i32, ClassSpec>>> = Lazy::new(|| {
+    let data = include_str!("../../../src/lib/data/json/SkillClassSpec.json");
+    let table: ClassSpecTable = serde_json::from_str(data).expect("invalid SkillClassSpec.json");
+    RwLock::new(table.skills)
+});
+
+/// Merges the user's override file (if any) into the bundled table
+pub fn load_class_spec_overrides(app_data_dir: &Path) {
+    let path = app_data_dir.join(CLASS_SPEC_OVERRIDE_FILE);
+    if !path.exists() {
+        return;
+    }
+    let table = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|data| serde_json::from_str::<ClassSpecTable>(&data).map_err(|e| e.to_string()))
+    {
+        Ok(table) => table,
+        Err(e) => {
+            warn!("Failed to load {}, ignoring: {e}", path.display());
+            return;
+        }
+    };
+    if table.version > CLASS_SPEC_TABLE_VERSION {
+        warn!(
+            "{} is version {}, this version of the app only supports up to {CLASS_SPEC_TABLE_VERSION}, ignoring",
+            path.display(),
+            table.version
+        );
+        return;
+    }
+    info!(
+        "Loaded {} class spec overrides from {}",
+        table.skills.len(),
+        path.display()
+    );
+    CLASS_SPEC_TABLE.write().unwrap().extend(table.skills);
+}
+
+/// Spec the skill is exclusive to, `Unknown` for skills shared by both specs (or unknown skills)
+pub fn get_class_spec_from_skill_id(skill_id: i32) -> ClassSpec {
+    CLASS_SPEC_TABLE
+        .read()
+        .unwrap()
+        .get(&skill_id)
+        .copied()
+        .unwrap_or(ClassSpec::Unknown)
+}
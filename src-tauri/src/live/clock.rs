@@ -0,0 +1,107 @@
+use blueprotobuf_lib::blueprotobuf;
+use log::{debug, info};
+use std::cell::Cell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Round trips longer than this can't be from our own clock, the offset is taken as-is then
+const MAX_ROUND_TRIP_MS: i64 = 10_000;
+
+/// Source of the timestamps stamped on combat data, swap it out to replay captures deterministically
+pub trait Clock {
+    fn now_ms(&self) -> u128;
+}
+
+/// Local system time
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WallClock;
+
+impl Clock for WallClock {
+    fn now_ms(&self) -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis()
+    }
+}
+
+/// Game server time, estimated from when the packet being processed was captured plus the offset reported by `SyncServerTime`.
+/// Using the capture time keeps a stalled processing loop from stretching encounters.
+#[derive(Debug, Default, Clone)]
+pub struct ServerClock {
+    offset_ms: Option<i64>, // server - local, None until the first SyncServerTime
+    local_ms: Option<u128>, // local time of what's being processed, the wall clock until set
+    last_now_ms: Cell<u128>,
+}
+
+impl ServerClock {
+    /// Set to the capture time before processing a packet, or to the current time for timer-driven checks
+    pub fn set_local_ms(&mut self, local_ms: u128) {
+        self.local_ms = Some(local_ms);
+    }
+
+    fn local_ms(&self) -> u128 {
+        self.local_ms.unwrap_or_else(|| WallClock.now_ms())
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn on_sync_server_time(&mut self, sync_server_time: &blueprotobuf::SyncServerTime) {
+        let Some(server_ms) = sync_server_time.server_milliseconds else {
+            return;
+        };
+        let local_ms = self.local_ms() as i64;
+        // client_milliseconds echoes when we sent the request, half the round trip is spent on the way back
+        let one_way_ms = sync_server_time
+            .client_milliseconds
+            .map(|client_ms| local_ms - client_ms)
+            .filter(|round_trip_ms| (0..=MAX_ROUND_TRIP_MS).contains(round_trip_ms))
+            .map_or(0, |round_trip_ms| round_trip_ms / 2);
+        let offset_ms = server_ms + one_way_ms - local_ms;
+        if self.offset_ms.is_none() {
+            info!("server clock synced, offset {offset_ms}ms");
+        } else {
+            debug!("server clock offset {offset_ms}ms");
+        }
+        self.offset_ms = Some(offset_ms);
+    }
+}
+
+impl Clock for ServerClock {
+    /// Current server time in ms, falls back to the local clock until synced.
+    /// Never goes backwards so encounter durations can't turn negative when the offset moves.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_possible_wrap)]
+    #[allow(clippy::cast_sign_loss)]
+    fn now_ms(&self) -> u128 {
+        let local_ms = self.local_ms();
+        let now_ms = match self.offset_ms {
+            Some(offset_ms) => (local_ms as i64 + offset_ms).max(0) as u128,
+            None => local_ms,
+        };
+        let now_ms = self.last_now_ms.get().max(now_ms);
+        self.last_now_ms.set(now_ms);
+        now_ms
+    }
+}
+
+/// Time taken from the packets themselves (e.g. capture timestamps of a recording), set before processing each packet.
+/// Replaying the same packets always yields the same encounter with it.
+#[cfg(test)]
+#[derive(Debug, Default, Clone)]
+pub struct PacketTimestampClock {
+    timestamp_ms: Cell<u128>,
+}
+
+#[cfg(test)]
+impl PacketTimestampClock {
+    pub fn set_timestamp_ms(&self, timestamp_ms: u128) {
+        self.timestamp_ms.set(timestamp_ms);
+    }
+}
+
+#[cfg(test)]
+impl Clock for PacketTimestampClock {
+    fn now_ms(&self) -> u128 {
+        self.timestamp_ms.get()
+    }
+}
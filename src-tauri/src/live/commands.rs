@@ -1,15 +1,43 @@
 use crate::WINDOW_LIVE_LABEL;
+use crate::history::db::{HistoryDb, HistoryDbMutex, HistoryQuery};
+use crate::live::body_part_tracker::BodyPart;
+use crate::live::boss_tracker::{DEFAULT_PHASE_THRESHOLDS_PCT, KILL_PROJECTION_WINDOW_MS};
 use crate::live::bptimer_state::{
     BPTimerEnabledMutex, set_bptimer_enabled as update_bptimer_state,
 };
-use crate::live::commands_models::{HeaderInfo, PlayerRow, PlayersWindow, SkillRow, SkillsWindow};
+use crate::live::buff_tracker::get_buff_name;
+use crate::live::character_profile::CharacterProfile;
+use crate::live::commands_models::{
+    BodyPartEntityRow, BodyPartPlayerRow, BodyPartRow, BodyPartStateChange, BodyPartsWindow,
+    BossPhaseRow, BossRow, BossesWindow, BuffEntityRow, BuffRow, BuffsWindow, CooldownRow,
+    CooldownRows, CooldownsWindow, DeathRecap, DeathRecapWindow, DeathRow, DeathsWindow,
+    DungeonPlayerRow, DungeonRunRow, DungeonRunsWindow, EncounterHistoryWindow, EncounterRow,
+    HateFlipRow, HateRow, HateRows, HatesWindow, HeaderInfo, HistoryWindow, HpPoint, IncomingRow,
+    LocationInfo, PassiveRow, PlayerRow, PlayersWindow, SkillRow, SkillsWindow, TargetRow,
+    TargetsWindow, TimelineRow, TimelineWindow,
+};
+use crate::live::cooldown_tracker::{CastEvent, count_casts, ready_time_ms};
+use crate::live::death_tracker::DeathRecord;
+use crate::live::dungeon_tracker::{DungeonRun, DungeonTracker};
+use crate::live::encounter_manager::{
+    CompletedEncounter, EncounterEndReason, EncounterManagerMutex,
+};
+use crate::live::event_log::{EVENT_LOGS_DIR, EventLog};
 use crate::live::opcodes_models::class::{Class, ClassSpec};
-use crate::live::opcodes_models::{CombatStats, Encounter, EncounterMutex, class};
-use crate::live::player_state::{PlayerCacheMutex, PlayerStateMutex};
+use crate::live::opcodes_models::{
+    CombatStats, Encounter, EncounterMutex, MONSTER_NAMES_BOSS, class, get_monster_name,
+};
+use crate::live::player_state::{PlayerCache, PlayerCacheMutex, PlayerState, PlayerStateMutex};
+use crate::live::rolling_window_state::{
+    RollingWindowSecsState, get_rolling_window_secs,
+    set_rolling_window_secs as update_rolling_window_secs,
+};
+use crate::live::skill_metadata::{SkillCategory, get_skill_metadata};
+use crate::live::timeline_tracker::{TIMELINE_BUCKET_MS, downsample};
 use crate::packets::packet_capture::request_restart;
-use blueprotobuf_lib::blueprotobuf::EEntityType;
-use log::info;
-use std::sync::MutexGuard;
+use blueprotobuf_lib::blueprotobuf::{BodyPartState, EEntityType};
+use log::{info, warn};
+use std::collections::HashMap;
 use tauri::Manager;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use window_vibrancy::{apply_blur, clear_blur};
@@ -52,10 +80,150 @@ pub fn copy_sync_container_data(app: tauri::AppHandle) {
     }
 }
 
+/// Writes the current encounter's event log to the app data dir, returns the file path
+#[tauri::command]
+#[specta::specta]
+pub fn dump_event_log(app: tauri::AppHandle) -> Result<String, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    // Copied so the packet loop isn't blocked while writing
+    let (event_log, time_fight_start_ms, dmg_stats, heal_stats) = {
+        let state = app.state::<EncounterMutex>();
+        let encounter = state.lock().unwrap();
+        (
+            encounter.event_log.clone(),
+            encounter.time_fight_start_ms,
+            encounter.dmg_stats.clone(),
+            encounter.heal_stats.clone(),
+        )
+    };
+    dump_event_log_impl(
+        &event_log,
+        time_fight_start_ms,
+        &dmg_stats,
+        &heal_stats,
+        &app_data_dir,
+    )
+}
+
+/// Same as `dump_event_log`, for the last completed encounter, older ones don't keep their event log
+#[tauri::command]
+#[specta::specta]
+pub fn dump_encounter_event_log(
+    app: tauri::AppHandle,
+    encounter_id: u32,
+) -> Result<String, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let (completed_encounter, event_log) = {
+        let state = app.state::<EncounterManagerMutex>();
+        let encounter_manager = state.lock().unwrap();
+        let completed_encounter = encounter_manager
+            .get(encounter_id)
+            .ok_or_else(|| format!("Could not find encounter with id {encounter_id}"))?;
+        let event_log = encounter_manager
+            .last_event_log
+            .as_ref()
+            .filter(|(id, _)| *id == encounter_id)
+            .map(|(_, event_log)| event_log.clone())
+            .ok_or_else(|| format!("Encounter {encounter_id} no longer has its event log"))?;
+        (completed_encounter.encounter.clone(), event_log)
+    };
+    dump_event_log_impl(
+        &event_log,
+        completed_encounter.time_fight_start_ms,
+        &completed_encounter.dmg_stats,
+        &completed_encounter.heal_stats,
+        &app_data_dir,
+    )
+}
+
+/// Writes `event_log` and checks replaying it gives back the live stats, never called with a lock held
+pub fn dump_event_log_impl(
+    event_log: &EventLog,
+    time_fight_start_ms: u128,
+    dmg_stats: &CombatStats,
+    heal_stats: &CombatStats,
+    app_data_dir: &std::path::Path,
+) -> Result<String, String> {
+    if event_log.events.is_empty() {
+        return Err("No events to dump".to_string());
+    }
+    let path = app_data_dir
+        .join(EVENT_LOGS_DIR)
+        .join(format!("encounter_{time_fight_start_ms}.jsonl"));
+    event_log
+        .dump(&path)
+        .map_err(|e| format!("Failed to dump event log: {e}"))?;
+    info!(
+        "dumped {} events to {}",
+        event_log.events.len(),
+        path.display()
+    );
+
+    // Replaying the log should give back the live totals, the dump can't be trusted otherwise
+    if event_log.dropped_events > 0 {
+        warn!(
+            "the event log is missing its {} oldest events",
+            event_log.dropped_events
+        );
+    } else if event_log.get_stats(|hit_event| !hit_event.is_heal) != *dmg_stats
+        || event_log.get_stats(|hit_event| hit_event.is_heal) != *heal_stats
+    {
+        warn!("replaying the event log doesn't match the live stats");
+    }
+    Ok(path.display().to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_character_profile(
+    state: tauri::State<'_, EncounterMutex>,
+) -> Result<CharacterProfile, String> {
+    let encounter = state.lock().unwrap();
+    get_character_profile_impl(&encounter)
+}
+
+pub fn get_character_profile_impl(encounter: &Encounter) -> Result<CharacterProfile, String> {
+    encounter
+        .local_player
+        .as_ref()
+        .and_then(|local_player| local_player.v_data.as_ref())
+        .and_then(|v_data| CharacterProfile::from_char_serialize(v_data, encounter.local_player_ms))
+        .ok_or_else(|| "No SyncContainerData received yet".to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_location(
+    player_state: tauri::State<'_, PlayerStateMutex>,
+) -> Result<LocationInfo, String> {
+    let player_state = player_state.lock().unwrap();
+    get_location_impl(&player_state)
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn get_location_impl(player_state: &PlayerState) -> Result<LocationInfo, String> {
+    let location_tracker = &player_state.location_tracker;
+    let location = location_tracker
+        .location
+        .clone()
+        .ok_or_else(|| "Location unknown until the next SyncContainerData".to_string())?;
+    Ok(LocationInfo {
+        is_dungeon: location.is_dungeon(),
+        location,
+        entered_ms: location_tracker
+            .entered_ms
+            .map_or(-1.0, |entered_ms| entered_ms as f64),
+    })
+}
+
 #[tauri::command]
 #[specta::specta]
-pub fn get_header_info(state: tauri::State<'_, EncounterMutex>) -> Result<HeaderInfo, String> {
+pub fn get_header_info(
+    state: tauri::State<'_, EncounterMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
+) -> Result<HeaderInfo, String> {
     let encounter = state.lock().unwrap();
+    let rolling_window_secs = get_rolling_window_secs(&rolling_window_secs_state);
     if encounter.dmg_stats.value == 0 {
         return Err("No damage found".to_string());
     }
@@ -72,9 +240,35 @@ pub fn get_header_info(state: tauri::State<'_, EncounterMutex>) -> Result<Header
         total_dmg: encounter_stats.value as f64,
         elapsed_ms: time_elapsed_ms as f64,
         time_last_combat_packet_ms: encounter.time_last_combat_packet_ms as f64,
+        rolling_dps: encounter.timeline_tracker.get_rolling_value_per_sec(
+            encounter.timeline_tracker.entity_uid_to_dmg.values(),
+            encounter.get_now_ms(),
+            rolling_window_secs,
+        ),
+        rolling_window_secs: f64::from(rolling_window_secs),
+        last_boss_pull_ms: get_last_boss_pull_ms(&encounter),
     })
 }
 
+fn is_boss(encounter: &Encounter, entity_uid: i64) -> bool {
+    encounter
+        .entity_uid_to_entity
+        .get(&entity_uid)
+        .and_then(|entity| entity.monster_id)
+        .is_some_and(|monster_id| MONSTER_NAMES_BOSS.contains_key(&monster_id))
+}
+
+/// When the local player last got on a boss' hate list, -1 if never. Lets the overlay alert on aggro pulls.
+#[allow(clippy::cast_precision_loss)]
+pub fn get_last_boss_pull_ms(encounter: &Encounter) -> f64 {
+    encounter
+        .hate_tracker
+        .last_pull
+        .as_ref()
+        .filter(|last_pull| is_boss(encounter, last_pull.entity_uid))
+        .map_or(-1.0, |last_pull| last_pull.timestamp_ms as f64)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn hard_reset(state: tauri::State<'_, EncounterMutex>) {
@@ -86,9 +280,13 @@ pub fn hard_reset(state: tauri::State<'_, EncounterMutex>) {
 
 #[tauri::command]
 #[specta::specta]
-pub fn reset_encounter(state: tauri::State<'_, EncounterMutex>) {
+pub fn reset_encounter(
+    state: tauri::State<'_, EncounterMutex>,
+    encounter_manager_state: tauri::State<'_, EncounterManagerMutex>,
+) {
     let mut encounter = state.lock().unwrap();
-    encounter.clone_from(&Encounter::default());
+    let mut encounter_manager = encounter_manager_state.lock().unwrap();
+    encounter_manager.close_encounter(&mut encounter, EncounterEndReason::Reset);
     info!("encounter reset");
 }
 
@@ -99,24 +297,443 @@ pub fn toggle_pause_encounter(state: tauri::State<'_, EncounterMutex>) {
     encounter.is_encounter_paused = !encounter.is_encounter_paused;
 }
 
-#[derive(Debug, Clone, Copy)]
+#[tauri::command]
+#[specta::specta]
+pub fn set_encounter_inactivity_timeout(
+    encounter_manager_state: tauri::State<'_, EncounterManagerMutex>,
+    timeout_secs: u32,
+) {
+    let mut encounter_manager = encounter_manager_state.lock().unwrap();
+    encounter_manager.inactivity_timeout_ms = u128::from(timeout_secs) * 1000;
+    info!("encounter inactivity timeout set to {timeout_secs}s");
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_encounter_history(
+    encounter_manager_state: tauri::State<'_, EncounterManagerMutex>,
+) -> EncounterHistoryWindow {
+    let encounter_manager = encounter_manager_state.lock().unwrap();
+    get_encounter_history_impl(&encounter_manager.history)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_dungeon_runs(
+    encounter_manager_state: tauri::State<'_, EncounterManagerMutex>,
+) -> DungeonRunsWindow {
+    let encounter_manager = encounter_manager_state.lock().unwrap();
+    get_dungeon_runs_impl(&encounter_manager.dungeon_tracker)
+}
+
+pub fn get_dungeon_runs_impl(dungeon_tracker: &DungeonTracker) -> DungeonRunsWindow {
+    let current_row = dungeon_tracker
+        .current_run
+        .iter()
+        .map(|dungeon_run| get_dungeon_run_row(dungeon_run, true));
+    let completed_rows = dungeon_tracker
+        .completed_runs
+        .iter()
+        .rev()
+        .map(|dungeon_run| get_dungeon_run_row(dungeon_run, false));
+    DungeonRunsWindow {
+        run_rows: current_row.chain(completed_rows).collect(),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn get_dungeon_run_row(dungeon_run: &DungeonRun, is_active: bool) -> DungeonRunRow {
+    let location = dungeon_run.location.as_ref();
+    let mut player_rows: Vec<DungeonPlayerRow> = dungeon_run
+        .uid_to_player
+        .iter()
+        .map(|(&uid, player)| DungeonPlayerRow {
+            uid: uid as f64,
+            name: player.name.clone().unwrap_or_default(),
+            class_name: class::get_class_name(
+                player.profession_id.map_or(Class::Unknown, Class::from),
+            ),
+            level: player.level.map_or(-1.0, f64::from),
+            ability_score: player
+                .ability_score
+                .map_or(-1.0, |ability_score| ability_score as f64),
+        })
+        .collect();
+    player_rows.sort_by(|this_row, other_row| this_row.name.cmp(&other_row.name));
+
+    DungeonRunRow {
+        scene_uuid: dungeon_run.scene_uuid as f64,
+        is_active,
+        map_id: location.and_then(|location| location.map_id).map(f64::from),
+        level_map_id: location
+            .and_then(|location| location.level_map_id)
+            .map(f64::from),
+        dungeon_guid: location.and_then(|location| location.dungeon_guid.clone()),
+        difficulty: dungeon_run.difficulty.map_or(-1.0, f64::from),
+        state: dungeon_run.state.as_str_name().to_string(),
+        result: dungeon_run.result.map_or(-1.0, f64::from),
+        start_ms: dungeon_run.start_ms as f64,
+        end_ms: dungeon_run.end_ms.map_or(-1.0, |end_ms| end_ms as f64),
+        clear_time_ms: dungeon_run
+            .clear_time_ms()
+            .map_or(-1.0, |clear_time_ms| clear_time_ms as f64),
+        pass_time: dungeon_run.pass_time.map_or(-1.0, f64::from),
+        total_score: dungeon_run.total_score.map_or(-1.0, f64::from),
+        master_mode_score: dungeon_run.master_mode_score.map_or(-1.0, f64::from),
+        affix_ids: dungeon_run
+            .affix_ids
+            .iter()
+            .map(|&affix_id| f64::from(affix_id))
+            .collect(),
+        player_rows,
+        encounter_ids: dungeon_run
+            .encounter_ids
+            .iter()
+            .map(|&encounter_id| f64::from(encounter_id))
+            .collect(),
+        history_encounter_ids: dungeon_run
+            .history_encounter_ids
+            .iter()
+            .map(|&history_encounter_id| history_encounter_id as f64)
+            .collect(),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn get_encounter_history_impl<'a>(
+    completed_encounters: impl IntoIterator<Item = &'a CompletedEncounter>,
+) -> EncounterHistoryWindow {
+    let mut encounter_rows: Vec<EncounterRow> = completed_encounters
+        .into_iter()
+        .map(|completed_encounter| {
+            let encounter = &completed_encounter.encounter;
+            let time_elapsed_ms =
+                encounter.time_last_combat_packet_ms - encounter.time_fight_start_ms;
+            let mut boss_names: Vec<String> = encounter
+                .boss_tracker
+                .boss_uid_to_hp_timeline
+                .keys()
+                .filter_map(|boss_uid| encounter.entity_uid_to_entity.get(boss_uid))
+                .filter_map(|entity| entity.monster_id)
+                .filter_map(|monster_id| MONSTER_NAMES_BOSS.get(&monster_id).cloned())
+                .collect();
+            boss_names.sort();
+            boss_names.dedup();
+            let v_data = encounter
+                .local_player
+                .as_ref()
+                .and_then(|local_player| local_player.v_data.as_ref());
+            let location = encounter.location.as_ref();
+            let top_player_name = encounter
+                .entity_uid_to_entity
+                .values()
+                .filter(|entity| entity.entity_type == EEntityType::EntChar)
+                .max_by_key(|entity| entity.dmg_stats.value)
+                .and_then(|entity| entity.name.clone())
+                .unwrap_or_default();
+            EncounterRow {
+                id: f64::from(completed_encounter.id),
+                end_reason: completed_encounter.end_reason.as_str().to_string(),
+                time_fight_start_ms: encounter.time_fight_start_ms as f64,
+                time_last_combat_packet_ms: encounter.time_last_combat_packet_ms as f64,
+                elapsed_ms: time_elapsed_ms as f64,
+                total_dmg: encounter.dmg_stats.value as f64,
+                total_dps: nan_is_zero(
+                    encounter.dmg_stats.value as f64 / (time_elapsed_ms as f64 / 1000.0),
+                ),
+                total_heal: encounter.heal_stats.value as f64,
+                player_count: encounter
+                    .entity_uid_to_entity
+                    .values()
+                    .filter(|entity| {
+                        entity.entity_type == EEntityType::EntChar
+                            && (entity.dmg_stats.value > 0 || entity.heal_stats.value > 0)
+                    })
+                    .count() as f64,
+                boss_names,
+                map_id: location.and_then(|location| location.map_id).map(f64::from),
+                line_id: location
+                    .and_then(|location| location.line_id)
+                    .map(f64::from),
+                level_map_id: location
+                    .and_then(|location| location.level_map_id)
+                    .map(f64::from),
+                dungeon_guid: location.and_then(|location| location.dungeon_guid.clone()),
+                local_player_name: v_data
+                    .and_then(|v_data| v_data.char_base.as_ref())
+                    .and_then(|char_base| char_base.name.clone())
+                    .unwrap_or_default(),
+                top_player_name,
+            }
+        })
+        .collect();
+
+    // Newest first
+    encounter_rows.sort_by(|this_row, other_row| other_row.id.total_cmp(&this_row.id));
+
+    EncounterHistoryWindow { encounter_rows }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_encounter_player_window(
+    encounter_manager_state: tauri::State<'_, EncounterManagerMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
+    encounter_id: u32,
+    stat_type: StatType,
+) -> Result<PlayersWindow, String> {
+    let encounter_manager = encounter_manager_state.lock().unwrap();
+    let completed_encounter = encounter_manager
+        .get(encounter_id)
+        .ok_or_else(|| format!("Could not find encounter with id {encounter_id}"))?;
+    let player_state = player_state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    Ok(get_player_window(
+        &completed_encounter.encounter,
+        stat_type,
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
+    ))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_encounter_skill_window(
+    encounter_manager_state: tauri::State<'_, EncounterManagerMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
+    encounter_id: u32,
+    player_uid_str: &str,
+    stat_type: StatType,
+    grouping: Option<SkillGrouping>,
+) -> Result<SkillsWindow, String> {
+    let player_uid = player_uid_str
+        .parse()
+        .map_err(|_| format!("Invalid player uid {player_uid_str}"))?;
+    let encounter_manager = encounter_manager_state.lock().unwrap();
+    let completed_encounter = encounter_manager
+        .get(encounter_id)
+        .ok_or_else(|| format!("Could not find encounter with id {encounter_id}"))?;
+    let player_state = player_state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_skill_window_impl(
+        &completed_encounter.encounter,
+        player_uid,
+        stat_type,
+        grouping.unwrap_or_default(),
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
+    )
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_history(
+    history_db_state: tauri::State<'_, HistoryDbMutex>,
+    query: HistoryQuery,
+) -> Result<HistoryWindow, String> {
+    let history_db = history_db_state.lock().unwrap();
+    get_history_impl(&history_db, &query)
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn get_history_impl(
+    history_db: &HistoryDb,
+    query: &HistoryQuery,
+) -> Result<HistoryWindow, String> {
+    let history_page = history_db
+        .query_encounters(query)
+        .map_err(|e| format!("Failed to query encounter history: {e}"))?;
+    let encounter_rows = history_page
+        .summaries
+        .into_iter()
+        .map(|summary| {
+            let mut boss_names: Vec<String> = summary
+                .monster_ids
+                .iter()
+                .filter_map(|monster_id| MONSTER_NAMES_BOSS.get(monster_id).cloned())
+                .collect();
+            boss_names.sort();
+            boss_names.dedup();
+            EncounterRow {
+                id: summary.id as f64,
+                end_reason: summary.end_reason,
+                time_fight_start_ms: summary.time_fight_start_ms as f64,
+                time_last_combat_packet_ms: summary.time_last_combat_packet_ms as f64,
+                elapsed_ms: summary.duration_ms as f64,
+                total_dmg: summary.total_dmg as f64,
+                total_dps: nan_is_zero(
+                    summary.total_dmg as f64 / (summary.duration_ms as f64 / 1000.0),
+                ),
+                total_heal: summary.total_heal as f64,
+                player_count: summary.player_count as f64,
+                boss_names,
+                map_id: summary.map_id.map(f64::from),
+                line_id: summary.line_id.map(f64::from),
+                level_map_id: summary.level_map_id.map(f64::from),
+                dungeon_guid: summary.dungeon_guid,
+                local_player_name: summary.local_player_name.unwrap_or_default(),
+                top_player_name: summary.top_player_name.unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok(HistoryWindow {
+        encounter_rows,
+        total_count: history_page.total_count as f64,
+        page: f64::from(history_page.page),
+        page_size: f64::from(history_page.page_size),
+    })
+}
+
+pub fn load_history_encounter(
+    history_db: &HistoryDb,
+    encounter_id: u32,
+) -> Result<Encounter, String> {
+    history_db
+        .load_encounter(i64::from(encounter_id))
+        .map_err(|e| format!("Failed to load encounter {encounter_id} from history: {e}"))?
+        .map(|saved_encounter| saved_encounter.to_encounter())
+        .ok_or_else(|| format!("Could not find saved encounter with id {encounter_id}"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_history_player_window(
+    history_db_state: tauri::State<'_, HistoryDbMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
+    encounter_id: u32,
+    stat_type: StatType,
+) -> Result<PlayersWindow, String> {
+    let encounter = load_history_encounter(&history_db_state.lock().unwrap(), encounter_id)?;
+    let player_state = player_state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    Ok(get_player_window(
+        &encounter,
+        stat_type,
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
+    ))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_history_skill_window(
+    history_db_state: tauri::State<'_, HistoryDbMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
+    encounter_id: u32,
+    player_uid_str: &str,
+    stat_type: StatType,
+    grouping: Option<SkillGrouping>,
+) -> Result<SkillsWindow, String> {
+    let player_uid = player_uid_str
+        .parse()
+        .map_err(|_| format!("Invalid player uid {player_uid_str}"))?;
+    let encounter = load_history_encounter(&history_db_state.lock().unwrap(), encounter_id)?;
+    let player_state = player_state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_skill_window_impl(
+        &encounter,
+        player_uid,
+        stat_type,
+        grouping.unwrap_or_default(),
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
+    )
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_history_character_profile(
+    history_db_state: tauri::State<'_, HistoryDbMutex>,
+    encounter_id: u32,
+) -> Result<Option<CharacterProfile>, String> {
+    get_history_character_profile_impl(&history_db_state.lock().unwrap(), encounter_id)
+}
+
+/// Gear the local player had in a saved encounter, `None` for encounters saved before profiles were kept
+pub fn get_history_character_profile_impl(
+    history_db: &HistoryDb,
+    encounter_id: u32,
+) -> Result<Option<CharacterProfile>, String> {
+    history_db
+        .load_encounter(i64::from(encounter_id))
+        .map_err(|e| format!("Failed to load encounter {encounter_id} from history: {e}"))?
+        .map(|saved_encounter| saved_encounter.character_profile)
+        .ok_or_else(|| format!("Could not find saved encounter with id {encounter_id}"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_history_encounter(
+    history_db_state: tauri::State<'_, HistoryDbMutex>,
+    encounter_id: u32,
+) -> Result<bool, String> {
+    history_db_state
+        .lock()
+        .unwrap()
+        .delete_encounter(i64::from(encounter_id))
+        .map_err(|e| format!("Failed to delete encounter {encounter_id} from history: {e}"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn clear_history(history_db_state: tauri::State<'_, HistoryDbMutex>) -> Result<(), String> {
+    history_db_state
+        .lock()
+        .unwrap()
+        .clear_encounters()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to clear history: {e}"))
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
 pub enum StatType {
     Dmg,
     DmgBossOnly,
     Heal,
 }
 
+#[derive(
+    specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum SkillGrouping {
+    #[default]
+    Skill, // one row per skill, summon hits included
+    Source, // the player's own hits first, then their summons' hits as separate rows
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_dps_player_window(
     state: tauri::State<'_, EncounterMutex>,
     player_cache_state: tauri::State<'_, PlayerCacheMutex>,
     player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
 ) -> PlayersWindow {
     let encounter = state.lock().unwrap();
-    let player_cache = player_cache_state.lock().unwrap();
     let player_state = player_state.lock().unwrap();
-    get_player_window(encounter, StatType::Dmg, &player_cache, &player_state)
+    let player_cache = player_cache_state.lock().unwrap();
+    get_player_window(
+        &encounter,
+        StatType::Dmg,
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
+    )
 }
 
 #[tauri::command]
@@ -125,11 +742,18 @@ pub fn get_heal_player_window(
     state: tauri::State<'_, EncounterMutex>,
     player_cache_state: tauri::State<'_, PlayerCacheMutex>,
     player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
 ) -> PlayersWindow {
     let encounter = state.lock().unwrap();
-    let player_cache = player_cache_state.lock().unwrap();
     let player_state = player_state.lock().unwrap();
-    get_player_window(encounter, StatType::Heal, &player_cache, &player_state)
+    let player_cache = player_cache_state.lock().unwrap();
+    get_player_window(
+        &encounter,
+        StatType::Heal,
+        &player_cache,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
+    )
 }
 
 #[tauri::command]
@@ -138,33 +762,43 @@ pub fn get_dps_boss_only_player_window(
     state: tauri::State<'_, EncounterMutex>,
     player_cache_state: tauri::State<'_, PlayerCacheMutex>,
     player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
 ) -> PlayersWindow {
     let encounter = state.lock().unwrap();
-    let player_cache = player_cache_state.lock().unwrap();
     let player_state = player_state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
     get_player_window(
-        encounter,
+        &encounter,
         StatType::DmgBossOnly,
         &player_cache,
         &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
     )
 }
 
 pub fn get_player_window(
-    encounter: MutexGuard<Encounter>,
+    encounter: &Encounter,
     stat_type: StatType,
-    player_cache: &std::sync::MutexGuard<crate::live::player_state::PlayerCache>,
-    player_state: &std::sync::MutexGuard<crate::live::player_state::PlayerState>,
+    player_cache: &PlayerCache,
+    player_state: &crate::live::player_state::PlayerState,
+    rolling_window_secs: u32,
 ) -> PlayersWindow {
     let time_elapsed_ms = encounter.time_last_combat_packet_ms - encounter.time_fight_start_ms;
     #[allow(clippy::cast_precision_loss)]
     let time_elapsed_secs = time_elapsed_ms as f64 / 1000.0;
+    let timeline_buckets = get_timeline_buckets(encounter, stat_type);
 
     #[allow(clippy::cast_precision_loss)]
     let mut player_window = PlayersWindow {
         player_rows: Vec::new(),
         local_player_uid: player_state.get_local_player_uid().unwrap_or(-1) as f64,
         top_value: 0.0,
+        rolling_window_secs: f64::from(rolling_window_secs),
+        group_rolling_value_per_sec: encounter.timeline_tracker.get_rolling_value_per_sec(
+            timeline_buckets.values(),
+            encounter.get_now_ms(),
+            rolling_window_secs,
+        ),
     };
     for (&entity_uid, entity) in &encounter.entity_uid_to_entity {
         // Select stats per player and encounter
@@ -217,12 +851,21 @@ pub fn get_player_window(
             lucky_value_rate: nan_is_zero(
                 entity_stats.lucky_value as f64 / entity_stats.value as f64 * 100.0,
             ),
+            rainbow_rate: nan_is_zero(
+                entity_stats.rainbow_hits as f64 / entity_stats.hits as f64 * 100.0,
+            ),
             hits: entity_stats.hits as f64,
             hits_per_minute: nan_is_zero(entity_stats.hits as f64 / time_elapsed_secs * 60.0),
+            active_dps: entity_stats.get_active_value_per_sec(),
+            active_time_ms: entity_stats.active_time_ms as f64,
+            rolling_value_per_sec: encounter.timeline_tracker.get_rolling_value_per_sec(
+                timeline_buckets.get(&entity_uid),
+                encounter.get_now_ms(),
+                rolling_window_secs,
+            ),
         };
         player_window.player_rows.push(damage_row);
     }
-    drop(encounter); // drop lock before expensive sort
 
     // Sort skills descending by damage dealt
     player_window.player_rows.sort_by(|this_row, other_row| {
@@ -241,17 +884,22 @@ pub fn get_dps_skill_window(
     state: tauri::State<'_, EncounterMutex>,
     player_cache_state: tauri::State<'_, PlayerCacheMutex>,
     player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
     player_uid_str: &str,
+    grouping: Option<SkillGrouping>,
 ) -> Result<SkillsWindow, String> {
     let player_uid = player_uid_str.parse().unwrap();
+    let encounter = state.lock().unwrap();
+    let player_state = player_state.lock().unwrap();
     let player_cache = player_cache_state.lock().unwrap();
-    let player_state_guard = player_state.lock().unwrap();
-    get_skill_window(
-        state,
+    get_skill_window_impl(
+        &encounter,
         player_uid,
         StatType::Dmg,
+        grouping.unwrap_or_default(),
         &player_cache,
-        &player_state_guard,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
     )
 }
 
@@ -261,17 +909,22 @@ pub fn get_dps_boss_only_skill_window(
     state: tauri::State<'_, EncounterMutex>,
     player_cache_state: tauri::State<'_, PlayerCacheMutex>,
     player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
     player_uid_str: &str,
+    grouping: Option<SkillGrouping>,
 ) -> Result<SkillsWindow, String> {
     let player_uid = player_uid_str.parse().unwrap();
+    let encounter = state.lock().unwrap();
+    let player_state = player_state.lock().unwrap();
     let player_cache = player_cache_state.lock().unwrap();
-    let player_state_guard = player_state.lock().unwrap();
-    get_skill_window(
-        state,
+    get_skill_window_impl(
+        &encounter,
         player_uid,
         StatType::DmgBossOnly,
+        grouping.unwrap_or_default(),
         &player_cache,
-        &player_state_guard,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
     )
 }
 
@@ -281,37 +934,33 @@ pub fn get_heal_skill_window(
     state: tauri::State<'_, EncounterMutex>,
     player_cache_state: tauri::State<'_, PlayerCacheMutex>,
     player_state: tauri::State<'_, PlayerStateMutex>,
+    rolling_window_secs_state: tauri::State<'_, RollingWindowSecsState>,
     player_uid_str: &str,
+    grouping: Option<SkillGrouping>,
 ) -> Result<SkillsWindow, String> {
     let player_uid = player_uid_str.parse().unwrap();
+    let encounter = state.lock().unwrap();
+    let player_state = player_state.lock().unwrap();
     let player_cache = player_cache_state.lock().unwrap();
-    let player_state_guard = player_state.lock().unwrap();
-    get_skill_window(
-        state,
+    get_skill_window_impl(
+        &encounter,
         player_uid,
         StatType::Heal,
+        grouping.unwrap_or_default(),
         &player_cache,
-        &player_state_guard,
+        &player_state,
+        get_rolling_window_secs(&rolling_window_secs_state),
     )
 }
 
-pub fn get_skill_window(
-    state: tauri::State<'_, EncounterMutex>,
-    player_uid: i64,
-    stat_type: StatType,
-    player_cache: &std::sync::MutexGuard<crate::live::player_state::PlayerCache>,
-    player_state: &std::sync::MutexGuard<crate::live::player_state::PlayerState>,
-) -> Result<SkillsWindow, String> {
-    let encounter = state.lock().unwrap();
-    get_skill_window_impl(encounter, player_uid, stat_type, player_cache, player_state)
-}
-
 pub fn get_skill_window_impl(
-    encounter: std::sync::MutexGuard<crate::live::opcodes_models::Encounter>,
+    encounter: &Encounter,
     player_uid: i64,
     stat_type: StatType,
-    player_cache: &std::sync::MutexGuard<crate::live::player_state::PlayerCache>,
-    player_state: &std::sync::MutexGuard<crate::live::player_state::PlayerState>,
+    grouping: SkillGrouping,
+    player_cache: &PlayerCache,
+    player_state: &crate::live::player_state::PlayerState,
+    rolling_window_secs: u32,
 ) -> Result<SkillsWindow, String> {
 
     let Some(player) = encounter.entity_uid_to_entity.get(&player_uid) else {
@@ -322,23 +971,27 @@ pub fn get_skill_window_impl(
     #[allow(clippy::cast_precision_loss)]
     let time_elapsed_secs = time_elapsed_ms as f64 / 1000.0;
 
-    let (player_stats, encounter_stats, skill_uid_to_stats) = match stat_type {
-        StatType::Dmg => (
-            &player.dmg_stats,
-            &encounter.dmg_stats,
-            &player.skill_uid_to_dps_stats,
-        ),
-        StatType::DmgBossOnly => (
-            &player.dmg_stats_boss_only,
-            &encounter.dmg_stats_boss_only,
-            &player.skill_uid_to_dps_stats_boss_only,
-        ),
-        StatType::Heal => (
-            &player.heal_stats,
-            &encounter.heal_stats,
-            &player.skill_uid_to_heal_stats,
-        ),
-    };
+    let (player_stats, encounter_stats, skill_uid_to_stats, skill_uid_to_source_stats) =
+        match stat_type {
+            StatType::Dmg => (
+                &player.dmg_stats,
+                &encounter.dmg_stats,
+                &player.skill_uid_to_dps_stats,
+                &player.skill_uid_to_source_dps_stats,
+            ),
+            StatType::DmgBossOnly => (
+                &player.dmg_stats_boss_only,
+                &encounter.dmg_stats_boss_only,
+                &player.skill_uid_to_dps_stats_boss_only,
+                &player.skill_uid_to_source_dps_stats_boss_only,
+            ),
+            StatType::Heal => (
+                &player.heal_stats,
+                &encounter.heal_stats,
+                &player.skill_uid_to_heal_stats,
+                &player.skill_uid_to_source_heal_stats,
+            ),
+        };
 
     // Player DPS Stats
     #[allow(clippy::cast_precision_loss)]
@@ -380,21 +1033,91 @@ pub fn get_skill_window_impl(
             lucky_value_rate: nan_is_zero(
                 player_stats.lucky_value as f64 / player_stats.value as f64 * 100.0,
             ),
+            rainbow_rate: nan_is_zero(
+                player_stats.rainbow_hits as f64 / player_stats.hits as f64 * 100.0,
+            ),
             hits: player_stats.hits as f64,
             hits_per_minute: nan_is_zero(player_stats.hits as f64 / time_elapsed_secs * 60.0),
+            active_dps: player_stats.get_active_value_per_sec(),
+            active_time_ms: player_stats.active_time_ms as f64,
+            rolling_value_per_sec: encounter.timeline_tracker.get_rolling_value_per_sec(
+                get_timeline_buckets(encounter, stat_type).get(&player_uid),
+                encounter.get_now_ms(),
+                rolling_window_secs,
+            ),
         },
         local_player_uid: player_state.get_local_player_uid().unwrap_or(-1) as f64,
         skill_rows: Vec::new(),
         top_value: 0.0,
+        summon_value: skill_uid_to_source_stats
+            .values()
+            .flatten()
+            .filter(|&(&source_uid, _)| source_uid != player_uid)
+            .map(|(_, summon_stat)| summon_stat.value)
+            .sum::<i64>() as f64,
+        passive_rows: Vec::new(),
     };
 
-    // Skills for this player
-    for (&skill_uid, skill_stat) in skill_uid_to_stats {
-        skill_window.top_value = skill_window.top_value.max(skill_stat.value as f64);
+    // Passives of this player, procs without any linked hits are listed too
+    if let Some(skill_id_to_passive_stats) = encounter
+        .passive_tracker
+        .actor_uid_to_passive_stats
+        .get(&player_uid)
+    {
         #[allow(clippy::cast_precision_loss)]
-        let skill_row = SkillRow {
+        for (&skill_id, passive_stats) in skill_id_to_passive_stats {
+            let passive_stat = match stat_type {
+                StatType::Dmg => &passive_stats.dmg_stats,
+                StatType::DmgBossOnly => &passive_stats.dmg_stats_boss_only,
+                StatType::Heal => &passive_stats.heal_stats,
+            };
+            skill_window.passive_rows.push(PassiveRow {
+                uid: f64::from(skill_id),
+                name: CombatStats::get_skill_name(skill_id),
+                procs: passive_stats.procs as f64,
+                procs_per_minute: nan_is_zero(
+                    passive_stats.procs as f64 / time_elapsed_secs * 60.0,
+                ),
+                total_value: passive_stat.value as f64,
+                value_pct: nan_is_zero(
+                    passive_stat.value as f64 / player_stats.value as f64 * 100.0,
+                ),
+                hits: passive_stat.hits as f64,
+            });
+        }
+        skill_window.passive_rows.sort_by(|this_row, other_row| {
+            other_row
+                .total_value
+                .partial_cmp(&this_row.total_value) // descending
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    other_row
+                        .procs
+                        .partial_cmp(&this_row.procs)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+        });
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let get_skill_row = |skill_uid: i32,
+                         skill_stat: &CombatStats,
+                         is_summon: bool,
+                         summon_uid: Option<i64>| {
+        let skill_metadata = get_skill_metadata(skill_uid);
+        SkillRow {
             uid: skill_uid as f64,
-            name: CombatStats::get_skill_name(skill_uid),
+            name: skill_metadata.name,
+            is_summon,
+            summon_uid: summon_uid.map_or(-1.0, |summon_uid| summon_uid as f64),
+            icon: skill_metadata.icon.unwrap_or_default(),
+            class_name: class::get_class_name(skill_metadata.class),
+            class_spec_name: class::get_class_spec(skill_metadata.class_spec),
+            category: if is_summon {
+                SkillCategory::Pet
+            } else {
+                skill_metadata.category
+            },
             total_value: skill_stat.value as f64,
             value_per_sec: nan_is_zero(skill_stat.value as f64 / time_elapsed_secs),
             value_pct: nan_is_zero(skill_stat.value as f64 / player_stats.value as f64 * 100.0),
@@ -406,22 +1129,841 @@ pub fn get_skill_window_impl(
             lucky_value_rate: nan_is_zero(
                 skill_stat.lucky_value as f64 / skill_stat.value as f64 * 100.0,
             ),
+            rainbow_rate: nan_is_zero(
+                skill_stat.rainbow_hits as f64 / skill_stat.hits as f64 * 100.0,
+            ),
             hits: skill_stat.hits as f64,
             hits_per_minute: nan_is_zero(skill_stat.hits as f64 / time_elapsed_secs * 60.0),
-        };
-        skill_window.skill_rows.push(skill_row);
+            active_dps: skill_stat.get_active_value_per_sec(),
+            active_time_ms: skill_stat.active_time_ms as f64,
+        }
+    };
+
+    // Skills for this player
+    for (&skill_uid, skill_stat) in skill_uid_to_stats {
+        // Saved encounters from before the split have no sources, they count as the player's own hits
+        let source_uid_to_stats = skill_uid_to_source_stats.get(&skill_uid);
+        match (grouping, source_uid_to_stats) {
+            // Split the skill into the player's own hits and the hits of each of their summons
+            (SkillGrouping::Source, Some(source_uid_to_stats)) => {
+                for (&source_uid, source_stat) in source_uid_to_stats {
+                    let summon_uid = (source_uid != player_uid).then_some(source_uid);
+                    skill_window.skill_rows.push(get_skill_row(
+                        skill_uid,
+                        source_stat,
+                        summon_uid.is_some(),
+                        summon_uid,
+                    ));
+                }
+            }
+            _ => {
+                let summon_uids: Vec<i64> = source_uid_to_stats
+                    .into_iter()
+                    .flat_map(HashMap::keys)
+                    .copied()
+                    .filter(|&source_uid| source_uid != player_uid)
+                    .collect();
+                let is_summon = !summon_uids.is_empty()
+                    && source_uid_to_stats.is_some_and(|source_uid_to_stats| {
+                        !source_uid_to_stats.contains_key(&player_uid)
+                    });
+                let summon_uid = match summon_uids.as_slice() {
+                    [summon_uid] if is_summon => Some(*summon_uid),
+                    _ => None,
+                };
+                skill_window
+                    .skill_rows
+                    .push(get_skill_row(skill_uid, skill_stat, is_summon, summon_uid));
+            }
+        }
     }
-    drop(encounter); // drop before expensive sort
+    skill_window.top_value = skill_window
+        .skill_rows
+        .iter()
+        .fold(0.0, |top_value, skill_row| {
+            top_value.max(skill_row.total_value)
+        });
 
-    // Sort skills descending by damage dealt
+    // Sort skills descending by damage dealt, grouped by source the summons go last
     skill_window.skill_rows.sort_by(|this_row, other_row| {
+        let by_source = match grouping {
+            SkillGrouping::Skill => std::cmp::Ordering::Equal,
+            SkillGrouping::Source => this_row.is_summon.cmp(&other_row.is_summon),
+        };
+        by_source.then_with(|| {
+            other_row
+                .total_value
+                .partial_cmp(&this_row.total_value) // descending
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    });
+
+    Ok(skill_window)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_buff_window(
+    state: tauri::State<'_, EncounterMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    player_state: tauri::State<'_, PlayerStateMutex>,
+) -> BuffsWindow {
+    let encounter = state.lock().unwrap();
+    let player_state = player_state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_buff_window_impl(&encounter, &player_cache, &player_state)
+}
+
+pub fn get_buff_window_impl(
+    encounter: &Encounter,
+    player_cache: &PlayerCache,
+    player_state: &crate::live::player_state::PlayerState,
+) -> BuffsWindow {
+    let time_elapsed_ms = encounter.time_last_combat_packet_ms - encounter.time_fight_start_ms;
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut buffs_window = BuffsWindow {
+        entity_rows: Vec::new(),
+        local_player_uid: player_state.get_local_player_uid().unwrap_or(-1) as f64,
+    };
+    for (&host_uid, base_id_to_buff_stats) in &encounter.buff_tracker.host_uid_to_buff_stats {
+        // Only players and bosses are interesting for uptime
+        let Some(entity) = encounter.entity_uid_to_entity.get(&host_uid) else {
+            continue;
+        };
+        let boss_name = entity
+            .monster_id
+            .and_then(|monster_id| MONSTER_NAMES_BOSS.get(&monster_id));
+        let name = match (entity.entity_type, boss_name) {
+            (EEntityType::EntChar, _) => entity
+                .name
+                .clone()
+                .or_else(|| player_cache.get_name(host_uid))
+                .unwrap_or_else(|| format!("Player {}", host_uid)),
+            (EEntityType::EntMonster, Some(boss_name)) => boss_name.clone(),
+            _ => continue,
+        };
+
+        #[allow(clippy::cast_precision_loss)]
+        let mut buff_rows: Vec<BuffRow> = base_id_to_buff_stats
+            .iter()
+            .map(|(&base_id, buff_stats)| {
+                let uptime_ms = encounter.buff_tracker.get_uptime_ms(
+                    host_uid,
+                    base_id,
+                    encounter.time_fight_start_ms,
+                    encounter.time_last_combat_packet_ms,
+                );
+                BuffRow {
+                    base_id: base_id as f64,
+                    name: get_buff_name(base_id),
+                    is_active: encounter.buff_tracker.is_active(
+                        host_uid,
+                        base_id,
+                        encounter.time_last_combat_packet_ms,
+                    ),
+                    uptime_ms: uptime_ms as f64,
+                    uptime_pct: nan_is_zero(uptime_ms as f64 / time_elapsed_ms as f64 * 100.0),
+                    applications: buff_stats.applications as f64,
+                    refreshes: buff_stats.refreshes as f64,
+                    removals: buff_stats.removals as f64,
+                    curr_layer: buff_stats.curr_layer as f64,
+                    max_layer: buff_stats.max_layer as f64,
+                    source_uids: buff_stats
+                        .source_uid_to_applications
+                        .keys()
+                        .map(|&source_uid| source_uid as f64)
+                        .collect(),
+                }
+            })
+            .collect();
+
+        // Sort buffs descending by uptime
+        buff_rows.sort_by(|this_row, other_row| {
+            other_row
+                .uptime_ms
+                .partial_cmp(&this_row.uptime_ms)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        #[allow(clippy::cast_precision_loss)]
+        buffs_window.entity_rows.push(BuffEntityRow {
+            uid: host_uid as f64,
+            name,
+            is_boss: boss_name.is_some(),
+            buff_rows,
+        });
+    }
+
+    buffs_window
+}
+
+fn get_entity_name(encounter: &Encounter, player_cache: &PlayerCache, entity_uid: i64) -> String {
+    let entity = encounter.entity_uid_to_entity.get(&entity_uid);
+    match entity.map(|entity| entity.entity_type) {
+        Some(EEntityType::EntMonster) => entity
+            .and_then(|entity| entity.monster_id)
+            .and_then(get_monster_name)
+            .or_else(|| entity.and_then(|entity| entity.name.clone()))
+            .unwrap_or_else(|| format!("Monster {}", entity_uid)),
+        _ => entity
+            .and_then(|entity| entity.name.clone())
+            .or_else(|| player_cache.get_name(entity_uid))
+            .unwrap_or_else(|| format!("Player {}", entity_uid)),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn get_death_row(
+    encounter: &Encounter,
+    player_cache: &PlayerCache,
+    death: &DeathRecord,
+) -> DeathRow {
+    DeathRow {
+        player_uid: death.player_uid as f64,
+        player_name: get_entity_name(encounter, player_cache, death.player_uid),
+        timestamp_ms: death.timestamp_ms as f64,
+        elapsed_ms: death
+            .timestamp_ms
+            .saturating_sub(encounter.time_fight_start_ms) as f64,
+        killer_uid: death.killer_uid.unwrap_or(-1) as f64,
+        killer_name: death
+            .killer_uid
+            .map(|killer_uid| get_entity_name(encounter, player_cache, killer_uid))
+            .unwrap_or_default(),
+        killer_skill_name: death
+            .killer_skill_uid
+            .map(CombatStats::get_skill_name)
+            .unwrap_or_default(),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_deaths_window(
+    state: tauri::State<'_, EncounterMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    player_state: tauri::State<'_, PlayerStateMutex>,
+) -> DeathsWindow {
+    let encounter = state.lock().unwrap();
+    let player_state = player_state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_deaths_window_impl(&encounter, &player_cache, &player_state)
+}
+
+pub fn get_deaths_window_impl(
+    encounter: &Encounter,
+    player_cache: &PlayerCache,
+    player_state: &crate::live::player_state::PlayerState,
+) -> DeathsWindow {
+    #[allow(clippy::cast_precision_loss)]
+    DeathsWindow {
+        death_rows: encounter
+            .death_tracker
+            .deaths
+            .iter()
+            .map(|death| get_death_row(encounter, player_cache, death))
+            .collect(),
+        local_player_uid: player_state.get_local_player_uid().unwrap_or(-1) as f64,
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_cooldown_window(
+    state: tauri::State<'_, EncounterMutex>,
+    player_state: tauri::State<'_, PlayerStateMutex>,
+) -> CooldownsWindow {
+    let encounter = state.lock().unwrap();
+    let player_state = player_state.lock().unwrap();
+    get_cooldown_window_impl(&encounter, &player_state)
+}
+
+pub fn get_cooldown_window_impl(
+    encounter: &Encounter,
+    player_state: &crate::live::player_state::PlayerState,
+) -> CooldownsWindow {
+    let window_start_ms = encounter.time_fight_start_ms;
+    let window_end_ms = encounter.time_last_combat_packet_ms;
+    #[allow(clippy::cast_precision_loss)]
+    let time_elapsed_mins = window_end_ms.saturating_sub(window_start_ms) as f64 / 60_000.0;
+
+    #[allow(clippy::cast_precision_loss)]
+    let get_cooldown_row = |uid: i32,
+                            name: String,
+                            is_fight_resource: bool,
+                            casts: &[CastEvent]| {
+        let cast_count = count_casts(casts, window_start_ms, window_end_ms);
+        let ready_time_ms = ready_time_ms(casts, window_start_ms, window_end_ms);
+        CooldownRow {
+            uid: f64::from(uid),
+            name,
+            is_fight_resource,
+            casts: cast_count as f64,
+            casts_per_minute: nan_is_zero(cast_count as f64 / time_elapsed_mins),
+            cooldown_ms: casts.last().map_or(0.0, |cast| cast.cooldown_ms as f64),
+            ready_time_ms: ready_time_ms as f64,
+            ready_pct: nan_is_zero(ready_time_ms as f64 / (time_elapsed_mins * 60_000.0) * 100.0),
+        }
+    };
+
+    let cooldown_tracker = &encounter.cooldown_tracker;
+    let mut cooldown_rows: CooldownRows = cooldown_tracker
+        .skill_uid_to_casts
+        .iter()
+        .map(|(&skill_uid, casts)| {
+            get_cooldown_row(
+                skill_uid,
+                CombatStats::get_skill_name(skill_uid),
+                false,
+                casts,
+            )
+        })
+        .chain(
+            cooldown_tracker
+                .res_id_to_casts
+                .iter()
+                .map(|(&res_id, casts)| {
+                    get_cooldown_row(res_id, format!("Resource ({res_id})"), true, casts)
+                }),
+        )
+        .filter(|cooldown_row| cooldown_row.casts > 0.0)
+        .collect();
+    cooldown_rows.sort_by(|this_row, other_row| {
+        other_row
+            .casts
+            .partial_cmp(&this_row.casts) // descending
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let total_casts: f64 = cooldown_rows
+        .iter()
+        .filter(|cooldown_row| !cooldown_row.is_fight_resource)
+        .map(|cooldown_row| cooldown_row.casts)
+        .sum();
+    #[allow(clippy::cast_precision_loss)]
+    CooldownsWindow {
+        local_player_uid: player_state.get_local_player_uid().unwrap_or(-1) as f64,
+        elapsed_ms: window_end_ms.saturating_sub(window_start_ms) as f64,
+        total_casts,
+        casts_per_minute: nan_is_zero(total_casts / time_elapsed_mins),
+        cooldown_rows,
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_hate_window(
+    state: tauri::State<'_, EncounterMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    player_state: tauri::State<'_, PlayerStateMutex>,
+) -> HatesWindow {
+    let encounter = state.lock().unwrap();
+    let player_state = player_state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_hate_window_impl(&encounter, &player_cache, &player_state)
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn get_hate_window_impl(
+    encounter: &Encounter,
+    player_cache: &PlayerCache,
+    player_state: &crate::live::player_state::PlayerState,
+) -> HatesWindow {
+    let window_start_ms = encounter.time_fight_start_ms;
+    let window_end_ms = encounter.time_last_combat_packet_ms;
+    let time_elapsed_ms = window_end_ms.saturating_sub(window_start_ms) as f64;
+
+    let mut hate_rows: HateRows = encounter
+        .hate_tracker
+        .entity_uid_to_hate_stats
+        .iter()
+        .map(|(&entity_uid, hate_stats)| {
+            let hold_time_ms = hate_stats.hold_time_ms(window_start_ms, window_end_ms) as f64;
+            HateRow {
+                uid: entity_uid as f64,
+                name: get_entity_name(encounter, player_cache, entity_uid),
+                monster_id: encounter
+                    .entity_uid_to_entity
+                    .get(&entity_uid)
+                    .and_then(|entity| entity.monster_id)
+                    .map_or(-1.0, f64::from),
+                is_boss: is_boss(encounter, entity_uid),
+                is_holding: hate_stats.is_holding(),
+                pulls: hate_stats.pulls as f64,
+                drops: hate_stats.drops as f64,
+                hold_time_ms,
+                hold_pct: nan_is_zero(hold_time_ms / time_elapsed_ms * 100.0),
+            }
+        })
+        .collect();
+    // Bosses first, then by how long we held them
+    hate_rows.sort_by(|this_row, other_row| {
+        other_row.is_boss.cmp(&this_row.is_boss).then_with(|| {
+            other_row
+                .hold_time_ms
+                .partial_cmp(&this_row.hold_time_ms) // descending
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    });
+
+    HatesWindow {
+        local_player_uid: player_state.get_local_player_uid().unwrap_or(-1) as f64,
+        last_boss_pull_ms: get_last_boss_pull_ms(encounter),
+        hate_rows,
+        // Newest first
+        flip_rows: encounter
+            .hate_tracker
+            .recent_flips
+            .iter()
+            .rev()
+            .map(|flip| HateFlipRow {
+                timestamp_ms: flip.timestamp_ms as f64,
+                elapsed_ms: flip.timestamp_ms.saturating_sub(window_start_ms) as f64,
+                entity_uid: flip.entity_uid as f64,
+                entity_name: get_entity_name(encounter, player_cache, flip.entity_uid),
+                is_boss: is_boss(encounter, flip.entity_uid),
+                is_gained: flip.is_gained,
+            })
+            .collect(),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_targets(
+    state: tauri::State<'_, EncounterMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+) -> TargetsWindow {
+    let encounter = state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_targets_impl(&encounter, &player_cache)
+}
+
+/// Monsters that took damage this encounter
+#[allow(clippy::cast_precision_loss)]
+pub fn get_targets_impl(encounter: &Encounter, player_cache: &PlayerCache) -> TargetsWindow {
+    let mut target_rows: Vec<TargetRow> = encounter
+        .entity_uid_to_entity
+        .iter()
+        .filter(|(_, entity)| {
+            entity.entity_type == EEntityType::EntMonster && entity.taken_dmg_stats.hits > 0
+        })
+        .map(|(&entity_uid, entity)| TargetRow {
+            uid: entity_uid as f64,
+            name: get_entity_name(encounter, player_cache, entity_uid),
+            monster_id: entity.monster_id.map_or(-1.0, f64::from),
+            is_boss: is_boss(encounter, entity_uid),
+            curr_hp: entity.curr_hp.map_or(-1.0, f64::from),
+            max_hp: entity.max_hp.map_or(-1.0, f64::from),
+            hp_pct: match (entity.curr_hp, entity.max_hp) {
+                (Some(curr_hp), Some(max_hp)) => {
+                    nan_is_zero(f64::from(curr_hp) / f64::from(max_hp) * 100.0)
+                }
+                _ => 0.0,
+            },
+            is_departed: entity.departed_ms.is_some(),
+            taken_value: entity.taken_dmg_stats.value as f64,
+            taken_hits: entity.taken_dmg_stats.hits as f64,
+            taken_pct: nan_is_zero(
+                entity.taken_dmg_stats.value as f64 / encounter.dmg_stats.value as f64 * 100.0,
+            ),
+        })
+        .collect();
+    // Bosses first, then by damage taken
+    target_rows.sort_by(|this_row, other_row| {
+        other_row.is_boss.cmp(&this_row.is_boss).then_with(|| {
+            other_row
+                .taken_value
+                .partial_cmp(&this_row.taken_value) // descending
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    });
+
+    TargetsWindow { target_rows }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_body_part_window(
+    state: tauri::State<'_, EncounterMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+) -> BodyPartsWindow {
+    let encounter = state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_body_part_window_impl(&encounter, &player_cache)
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn get_body_part_window_impl(
+    encounter: &Encounter,
+    player_cache: &PlayerCache,
+) -> BodyPartsWindow {
+    let mut entity_rows: Vec<BodyPartEntityRow> = encounter
+        .body_part_tracker
+        .entity_uid_to_parts
+        .iter()
+        .map(|(&entity_uid, parts)| {
+            let mut part_rows: Vec<BodyPartRow> = parts
+                .iter()
+                .map(|(&part_id, body_part)| {
+                    get_body_part_row(encounter, player_cache, part_id, body_part)
+                })
+                .collect();
+            part_rows.sort_by_key(|part_row| part_row.part_id as i64);
+            BodyPartEntityRow {
+                uid: entity_uid as f64,
+                name: get_entity_name(encounter, player_cache, entity_uid),
+                monster_id: encounter
+                    .entity_uid_to_entity
+                    .get(&entity_uid)
+                    .and_then(|entity| entity.monster_id)
+                    .map_or(-1.0, f64::from),
+                is_boss: is_boss(encounter, entity_uid),
+                total_value: part_rows.iter().map(|part_row| part_row.total_value).sum(),
+                part_rows,
+            }
+        })
+        .collect();
+    // Bosses first, then by damage dealt to their parts
+    entity_rows.sort_by(|this_row, other_row| {
+        other_row.is_boss.cmp(&this_row.is_boss).then_with(|| {
+            other_row
+                .total_value
+                .partial_cmp(&this_row.total_value) // descending
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    });
+
+    BodyPartsWindow { entity_rows }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn get_body_part_row(
+    encounter: &Encounter,
+    player_cache: &PlayerCache,
+    part_id: i32,
+    body_part: &BodyPart,
+) -> BodyPartRow {
+    let fight_start_ms = encounter.time_fight_start_ms;
+    let total_value: i64 = body_part
+        .attacker_uid_to_dmg
+        .values()
+        .map(|part_dmg| part_dmg.value)
+        .sum();
+
+    let mut player_rows: Vec<BodyPartPlayerRow> = body_part
+        .attacker_uid_to_dmg
+        .iter()
+        .map(|(&attacker_uid, part_dmg)| {
+            let entity = encounter.entity_uid_to_entity.get(&attacker_uid);
+            BodyPartPlayerRow {
+                uid: attacker_uid as f64,
+                name: get_entity_name(encounter, player_cache, attacker_uid),
+                class_name: class::get_class_name(
+                    entity
+                        .and_then(|entity| entity.class)
+                        .or_else(|| player_cache.get_class(attacker_uid))
+                        .unwrap_or(Class::Unknown),
+                ),
+                class_spec_name: class::get_class_spec(
+                    entity
+                        .and_then(|entity| entity.class_spec)
+                        .or_else(|| player_cache.get_class_spec(attacker_uid))
+                        .unwrap_or(ClassSpec::Unknown),
+                ),
+                total_value: part_dmg.value as f64,
+                value_pct: nan_is_zero(part_dmg.value as f64 / total_value as f64 * 100.0),
+                hits: part_dmg.hits as f64,
+            }
+        })
+        .collect();
+    player_rows.sort_by(|this_row, other_row| {
         other_row
             .total_value
             .partial_cmp(&this_row.total_value) // descending
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    Ok(skill_window)
+    let broken_ms = body_part.broken_ms();
+    BodyPartRow {
+        part_id: f64::from(part_id),
+        state: get_body_part_state_name(body_part.state),
+        curr_hp: body_part.hp.map_or(-1.0, |hp| hp as f64),
+        max_hp: body_part.max_hp.map_or(-1.0, |max_hp| max_hp as f64),
+        hp_pct: match (body_part.hp, body_part.max_hp) {
+            (Some(hp), Some(max_hp)) => nan_is_zero(hp as f64 / max_hp as f64 * 100.0),
+            _ => 0.0,
+        },
+        total_value: total_value as f64,
+        hits: player_rows.iter().map(|player_row| player_row.hits).sum(),
+        broken_ms: broken_ms.map_or(-1.0, |broken_ms| broken_ms as f64),
+        broken_elapsed_ms: broken_ms.map_or(-1.0, |broken_ms| {
+            broken_ms.saturating_sub(fight_start_ms) as f64
+        }),
+        state_changes: body_part
+            .state_changes
+            .iter()
+            .map(|&(timestamp_ms, state)| BodyPartStateChange {
+                timestamp_ms: timestamp_ms as f64,
+                elapsed_ms: timestamp_ms.saturating_sub(fight_start_ms) as f64,
+                state: get_body_part_state_name(state),
+            })
+            .collect(),
+        player_rows,
+    }
+}
+
+fn get_body_part_state_name(state: BodyPartState) -> String {
+    match state {
+        BodyPartState::Default => "Default",
+        BodyPartState::Injury => "Injury",
+        BodyPartState::Dead => "Dead",
+    }
+    .to_string()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_death_recap(
+    state: tauri::State<'_, EncounterMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    player_uid_str: &str,
+    max_events: u32,
+) -> Result<DeathRecapWindow, String> {
+    let player_uid = player_uid_str
+        .parse()
+        .map_err(|_| format!("Invalid player uid {player_uid_str}"))?;
+    let encounter = state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_death_recap_impl(&encounter, &player_cache, player_uid, max_events as usize)
+}
+
+pub fn get_death_recap_impl(
+    encounter: &Encounter,
+    player_cache: &PlayerCache,
+    player_uid: i64,
+    max_events: usize,
+) -> Result<DeathRecapWindow, String> {
+    if !encounter.entity_uid_to_entity.contains_key(&player_uid) {
+        return Err(format!("Could not find player with uid {player_uid}"));
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let death_recaps = encounter
+        .death_tracker
+        .get_deaths(player_uid)
+        .map(|death| DeathRecap {
+            death: get_death_row(encounter, player_cache, death),
+            // Last N events before the death, newest first
+            incoming_rows: death
+                .recap
+                .iter()
+                .rev()
+                .take(max_events)
+                .map(|incoming_event| IncomingRow {
+                    timestamp_ms: incoming_event.timestamp_ms as f64,
+                    ms_before_death: death
+                        .timestamp_ms
+                        .saturating_sub(incoming_event.timestamp_ms)
+                        as f64,
+                    source_uid: incoming_event.source_uid as f64,
+                    source_name: get_entity_name(
+                        encounter,
+                        player_cache,
+                        incoming_event.source_uid,
+                    ),
+                    skill_uid: incoming_event.skill_uid as f64,
+                    skill_name: CombatStats::get_skill_name(incoming_event.skill_uid),
+                    value: incoming_event.value as f64,
+                    is_heal: incoming_event.is_heal,
+                    is_crit: incoming_event.is_crit,
+                    is_lucky: incoming_event.is_lucky,
+                    is_killing_blow: incoming_event.is_dead,
+                })
+                .collect(),
+        })
+        .collect();
+
+    #[allow(clippy::cast_precision_loss)]
+    Ok(DeathRecapWindow {
+        player_uid: player_uid as f64,
+        player_name: get_entity_name(encounter, player_cache, player_uid),
+        death_recaps,
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_boss_window(
+    state: tauri::State<'_, EncounterMutex>,
+    phase_thresholds_pct: Option<Vec<f64>>,
+) -> BossesWindow {
+    let encounter = state.lock().unwrap();
+    get_boss_window_impl(
+        &encounter,
+        &phase_thresholds_pct.unwrap_or_else(|| DEFAULT_PHASE_THRESHOLDS_PCT.to_vec()),
+    )
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn get_boss_window_impl(encounter: &Encounter, phase_thresholds_pct: &[f64]) -> BossesWindow {
+    let boss_tracker = &encounter.boss_tracker;
+    let mut boss_rows: Vec<BossRow> = boss_tracker
+        .boss_uid_to_hp_timeline
+        .iter()
+        .filter_map(|(&boss_uid, timeline)| {
+            let last_sample = timeline.last()?;
+            let monster_id = encounter
+                .entity_uid_to_entity
+                .get(&boss_uid)
+                .and_then(|entity| entity.monster_id)
+                .unwrap_or_default();
+            Some(BossRow {
+                uid: boss_uid as f64,
+                monster_id: f64::from(monster_id),
+                name: MONSTER_NAMES_BOSS
+                    .get(&monster_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Monster {boss_uid}")),
+                curr_hp: last_sample.curr_hp as f64,
+                max_hp: last_sample.max_hp as f64,
+                hp_pct: last_sample.hp_pct(),
+                is_dead: last_sample.curr_hp <= 0,
+                recent_dps: boss_tracker.get_recent_dps(
+                    boss_uid,
+                    KILL_PROJECTION_WINDOW_MS,
+                    encounter.get_now_ms(),
+                ),
+                projected_kill_ms: boss_tracker
+                    .get_projected_kill_ms(
+                        boss_uid,
+                        KILL_PROJECTION_WINDOW_MS,
+                        encounter.get_now_ms(),
+                    )
+                    .map_or(-1.0, |projected_kill_ms| projected_kill_ms as f64),
+                phase_rows: boss_tracker
+                    .get_phases(boss_uid, phase_thresholds_pct)
+                    .into_iter()
+                    .map(|phase| BossPhaseRow {
+                        index: phase.index as f64,
+                        start_threshold_pct: phase.start_threshold_pct,
+                        start_ms: phase.start_ms as f64,
+                        end_ms: phase.end_ms.map_or(-1.0, |end_ms| end_ms as f64),
+                        duration_ms: phase
+                            .end_ms
+                            .unwrap_or(last_sample.timestamp_ms)
+                            .saturating_sub(phase.start_ms)
+                            as f64,
+                    })
+                    .collect(),
+                hp_timeline: timeline
+                    .iter()
+                    .map(|sample| HpPoint {
+                        timestamp_ms: sample.timestamp_ms as f64,
+                        hp_pct: sample.hp_pct(),
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+
+    // Alive bosses first, then by max HP
+    boss_rows.sort_by(|this_row, other_row| {
+        this_row
+            .is_dead
+            .cmp(&other_row.is_dead)
+            .then(other_row.max_hp.total_cmp(&this_row.max_hp))
+    });
+
+    BossesWindow { boss_rows }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_timeline(
+    state: tauri::State<'_, EncounterMutex>,
+    player_cache_state: tauri::State<'_, PlayerCacheMutex>,
+    stat_type: StatType,
+    bucket_secs: Option<u32>,
+    max_points: Option<u32>,
+) -> TimelineWindow {
+    let encounter = state.lock().unwrap();
+    let player_cache = player_cache_state.lock().unwrap();
+    get_timeline_impl(
+        &encounter,
+        &player_cache,
+        stat_type,
+        bucket_secs.unwrap_or(1),
+        max_points,
+    )
+}
+
+fn get_timeline_buckets(encounter: &Encounter, stat_type: StatType) -> &HashMap<i64, Vec<i64>> {
+    let timeline_tracker = &encounter.timeline_tracker;
+    match stat_type {
+        StatType::Dmg => &timeline_tracker.entity_uid_to_dmg,
+        StatType::DmgBossOnly => &timeline_tracker.entity_uid_to_dmg_boss_only,
+        StatType::Heal => &timeline_tracker.entity_uid_to_heal,
+    }
+}
+
+/// Per-second series, `bucket_secs` seconds are merged per point and more are merged if there'd be over `max_points`
+#[allow(clippy::cast_precision_loss)]
+pub fn get_timeline_impl(
+    encounter: &Encounter,
+    player_cache: &PlayerCache,
+    stat_type: StatType,
+    bucket_secs: u32,
+    max_points: Option<u32>,
+) -> TimelineWindow {
+    let timeline_tracker = &encounter.timeline_tracker;
+    let entity_uid_to_buckets = get_timeline_buckets(encounter, stat_type);
+    let len = timeline_tracker.get_len();
+    let mut bucket_secs = bucket_secs.max(1) as usize;
+    if let Some(max_points) = max_points.filter(|&max_points| max_points > 0) {
+        bucket_secs = bucket_secs.max(len.div_ceil(max_points as usize));
+    }
+
+    let mut group_buckets = vec![0; len];
+    let mut timeline_rows: Vec<TimelineRow> = entity_uid_to_buckets
+        .iter()
+        .map(|(&entity_uid, buckets)| {
+            for (group_value, value) in group_buckets.iter_mut().zip(buckets) {
+                *group_value += value;
+            }
+            let entity = encounter.entity_uid_to_entity.get(&entity_uid);
+            TimelineRow {
+                uid: entity_uid as f64,
+                name: get_entity_name(encounter, player_cache, entity_uid),
+                class_name: class::get_class_name(
+                    entity
+                        .and_then(|entity| entity.class)
+                        .or_else(|| player_cache.get_class(entity_uid))
+                        .unwrap_or(Class::Unknown),
+                ),
+                class_spec_name: class::get_class_spec(
+                    entity
+                        .and_then(|entity| entity.class_spec)
+                        .or_else(|| player_cache.get_class_spec(entity_uid))
+                        .unwrap_or(ClassSpec::Unknown),
+                ),
+                total_value: buckets.iter().sum::<i64>() as f64,
+                points: downsample(buckets, len, bucket_secs),
+            }
+        })
+        .collect();
+
+    // Sort descending by total value
+    timeline_rows
+        .sort_by(|this_row, other_row| other_row.total_value.total_cmp(&this_row.total_value));
+
+    TimelineWindow {
+        start_ms: timeline_tracker.start_ms.unwrap_or_default() as f64,
+        bucket_ms: (bucket_secs as u128 * TIMELINE_BUCKET_MS) as f64,
+        group_points: downsample(&group_buckets, len, bucket_secs),
+        timeline_rows,
+    }
 }
 
 #[tauri::command]
@@ -444,8 +1986,12 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 10_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
             PlayerRow {
                 uid: 10_000_002.0,
@@ -460,8 +2006,12 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 6_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
             PlayerRow {
                 uid: 10_000_003.0,
@@ -476,8 +2026,12 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 6_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
             PlayerRow {
                 uid: 10_000_004.0,
@@ -492,8 +2046,12 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 6_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
             PlayerRow {
                 uid: 10_000_005.0,
@@ -508,8 +2066,12 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 6_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
             PlayerRow {
                 uid: 10_000_006.0,
@@ -524,8 +2086,12 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 6_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
             PlayerRow {
                 uid: 10_000_007.0,
@@ -540,8 +2106,12 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 6_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
             PlayerRow {
                 uid: 10_000_008.0,
@@ -556,8 +2126,12 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 6_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
             PlayerRow {
                 uid: 10_000_009.0,
@@ -572,12 +2146,18 @@ pub fn get_test_player_window() -> PlayersWindow {
                 crit_value_rate: 2.0,
                 lucky_rate: 0.10,
                 lucky_value_rate: 1.5,
+                rainbow_rate: 0.05,
                 hits: 200.0,
                 hits_per_minute: 3.3,
+                active_dps: 6_000.6,
+                active_time_ms: 60_000.0,
+                rolling_value_per_sec: 9_500.0,
             },
         ],
         local_player_uid: 10_000_001.0,
         top_value: 100_000.0,
+        rolling_window_secs: 15.0,
+        group_rolling_value_per_sec: 58_005.4,
     }
 }
 
@@ -591,6 +2171,13 @@ pub fn set_bptimer_enabled(state: tauri::State<BPTimerEnabledMutex>, enabled: bo
     );
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn set_rolling_window_secs(state: tauri::State<RollingWindowSecsState>, window_secs: u32) {
+    update_rolling_window_secs(&state, window_secs);
+    info!("rolling DPS window set to {window_secs}s");
+}
+
 #[tauri::command]
 #[specta::specta]
 #[allow(clippy::too_many_lines)]
@@ -609,13 +2196,23 @@ pub fn get_test_skill_window(_player_uid: String) -> Result<SkillsWindow, String
             crit_value_rate: 2.0,
             lucky_rate: 0.10,
             lucky_value_rate: 1.5,
+            rainbow_rate: 0.05,
             hits: 200.0,
             hits_per_minute: 3.3,
+            active_dps: 10_000.6,
+            active_time_ms: 60_000.0,
+            rolling_value_per_sec: 9_500.0,
         },
         skill_rows: vec![
             SkillRow {
                 uid: 3602.0,
                 name: "Skill 1".to_string(),
+                is_summon: false,
+                summon_uid: -1.0,
+                icon: "weapon_tdl-01_tg01".to_string(),
+                class_name: "Stormblade".to_string(),
+                class_spec_name: "Iaido".to_string(),
+                category: SkillCategory::Special,
                 total_value: 100_000.0,
                 value_per_sec: 5_000.0,
                 value_pct: 80.0,
@@ -623,12 +2220,21 @@ pub fn get_test_skill_window(_player_uid: String) -> Result<SkillsWindow, String
                 crit_value_rate: 2.1,
                 lucky_rate: 0.12,
                 lucky_value_rate: 1.4,
+                rainbow_rate: 0.05,
                 hits: 80.0,
                 hits_per_minute: 1.5,
+                active_dps: 5_000.0,
+                active_time_ms: 60_000.0,
             },
             SkillRow {
                 uid: 3602.0,
                 name: "Skill 2".to_string(),
+                is_summon: false,
+                summon_uid: -1.0,
+                icon: "weapon_tdl-01_tg01".to_string(),
+                class_name: "Stormblade".to_string(),
+                class_spec_name: "Iaido".to_string(),
+                category: SkillCategory::Special,
                 total_value: 50_000.0,
                 value_per_sec: 7_345.6,
                 value_pct: 70.0,
@@ -636,12 +2242,21 @@ pub fn get_test_skill_window(_player_uid: String) -> Result<SkillsWindow, String
                 crit_value_rate: 1.9,
                 lucky_rate: 0.08,
                 lucky_value_rate: 1.3,
+                rainbow_rate: 0.05,
                 hits: 120.0,
                 hits_per_minute: 1.8,
+                active_dps: 7_345.6,
+                active_time_ms: 60_000.0,
             },
             SkillRow {
                 uid: 3602.0,
                 name: "Skill 3".to_string(),
+                is_summon: false,
+                summon_uid: -1.0,
+                icon: "weapon_tdl-01_tg01".to_string(),
+                class_name: "Stormblade".to_string(),
+                class_spec_name: "Iaido".to_string(),
+                category: SkillCategory::Special,
                 total_value: 33_000.0,
                 value_per_sec: 7_345.6,
                 value_pct: 60.0,
@@ -649,12 +2264,21 @@ pub fn get_test_skill_window(_player_uid: String) -> Result<SkillsWindow, String
                 crit_value_rate: 1.9,
                 lucky_rate: 0.08,
                 lucky_value_rate: 1.3,
+                rainbow_rate: 0.05,
                 hits: 120.0,
                 hits_per_minute: 1.8,
+                active_dps: 7_345.6,
+                active_time_ms: 60_000.0,
             },
             SkillRow {
                 uid: 3602.0,
                 name: "Skill 4".to_string(),
+                is_summon: false,
+                summon_uid: -1.0,
+                icon: "weapon_tdl-01_tg01".to_string(),
+                class_name: "Stormblade".to_string(),
+                class_spec_name: "Iaido".to_string(),
+                category: SkillCategory::Special,
                 total_value: 23_000.0,
                 value_per_sec: 7_345.6,
                 value_pct: 50.0,
@@ -662,12 +2286,21 @@ pub fn get_test_skill_window(_player_uid: String) -> Result<SkillsWindow, String
                 crit_value_rate: 1.9,
                 lucky_rate: 0.08,
                 lucky_value_rate: 1.3,
+                rainbow_rate: 0.05,
                 hits: 120.0,
                 hits_per_minute: 1.8,
+                active_dps: 7_345.6,
+                active_time_ms: 60_000.0,
             },
             SkillRow {
                 uid: 3602.0,
                 name: "Skill 5".to_string(),
+                is_summon: false,
+                summon_uid: -1.0,
+                icon: "weapon_tdl-01_tg01".to_string(),
+                class_name: "Stormblade".to_string(),
+                class_spec_name: "Iaido".to_string(),
+                category: SkillCategory::Special,
                 total_value: 11_000.0,
                 value_per_sec: 7_345.6,
                 value_pct: 40.0,
@@ -675,12 +2308,21 @@ pub fn get_test_skill_window(_player_uid: String) -> Result<SkillsWindow, String
                 crit_value_rate: 1.9,
                 lucky_rate: 0.08,
                 lucky_value_rate: 1.3,
+                rainbow_rate: 0.05,
                 hits: 120.0,
                 hits_per_minute: 1.8,
+                active_dps: 7_345.6,
+                active_time_ms: 60_000.0,
             },
             SkillRow {
                 uid: 3602.0,
                 name: "Skill 6".to_string(),
+                is_summon: false,
+                summon_uid: -1.0,
+                icon: "weapon_tdl-01_tg01".to_string(),
+                class_name: "Stormblade".to_string(),
+                class_spec_name: "Iaido".to_string(),
+                category: SkillCategory::Special,
                 total_value: 1_000.0,
                 value_per_sec: 7_345.6,
                 value_pct: 30.0,
@@ -688,12 +2330,21 @@ pub fn get_test_skill_window(_player_uid: String) -> Result<SkillsWindow, String
                 crit_value_rate: 1.9,
                 lucky_rate: 0.08,
                 lucky_value_rate: 1.3,
+                rainbow_rate: 0.05,
                 hits: 120.0,
                 hits_per_minute: 1.8,
+                active_dps: 7_345.6,
+                active_time_ms: 60_000.0,
             },
             SkillRow {
                 uid: 3602.0,
                 name: "Skill 7".to_string(),
+                is_summon: false,
+                summon_uid: -1.0,
+                icon: "weapon_tdl-01_tg01".to_string(),
+                class_name: "Stormblade".to_string(),
+                class_spec_name: "Iaido".to_string(),
+                category: SkillCategory::Special,
                 total_value: 400.0,
                 value_per_sec: 7_345.6,
                 value_pct: 20.0,
@@ -701,11 +2352,24 @@ pub fn get_test_skill_window(_player_uid: String) -> Result<SkillsWindow, String
                 crit_value_rate: 1.9,
                 lucky_rate: 0.08,
                 lucky_value_rate: 1.3,
+                rainbow_rate: 0.05,
                 hits: 120.0,
                 hits_per_minute: 1.8,
+                active_dps: 7_345.6,
+                active_time_ms: 60_000.0,
             },
         ],
         local_player_uid: 10_000_001.0,
         top_value: 100_000.0,
+        summon_value: 0.0,
+        passive_rows: vec![PassiveRow {
+            uid: 2_205_470.0,
+            name: "Passive 1".to_string(),
+            procs: 12.0,
+            procs_per_minute: 6.0,
+            total_value: 20_000.0,
+            value_pct: 8.0,
+            hits: 12.0,
+        }],
     })
 }
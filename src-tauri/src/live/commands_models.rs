@@ -2,6 +2,9 @@
 f64 is used in the models even when it doesn't make sense due to limitations with serde serializing u128 as a JSON number instead of a string
 */
 
+use crate::live::location_tracker::Location;
+use crate::live::skill_metadata::SkillCategory;
+
 #[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct HeaderInfo {
@@ -9,6 +12,17 @@ pub struct HeaderInfo {
     pub total_dmg: f64,
     pub elapsed_ms: f64,
     pub time_last_combat_packet_ms: f64,
+    pub rolling_dps: f64, // group DPS over the last rolling_window_secs
+    pub rolling_window_secs: f64,
+    pub last_boss_pull_ms: f64, // last time the local player got on a boss' hate list, -1 if never
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationInfo {
+    pub location: Location,
+    pub is_dungeon: bool,
+    pub entered_ms: f64, // -1 if unknown
 }
 
 #[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
@@ -17,6 +31,8 @@ pub struct PlayersWindow {
     pub player_rows: PlayerRows,
     pub local_player_uid: f64,
     pub top_value: f64,
+    pub rolling_window_secs: f64,
+    pub group_rolling_value_per_sec: f64,
 }
 
 pub type PlayerRows = Vec<PlayerRow>;
@@ -37,8 +53,12 @@ pub struct PlayerRow {
     pub crit_value_rate: f64,
     pub lucky_rate: f64,
     pub lucky_value_rate: f64,
+    pub rainbow_rate: f64,
     pub hits: f64,
     pub hits_per_minute: f64,
+    pub active_dps: f64,            // value per second of active time
+    pub active_time_ms: f64,        // time spent hitting, gaps over the threshold excluded
+    pub rolling_value_per_sec: f64, // over the last rolling_window_secs of the window
 }
 
 #[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
@@ -48,6 +68,23 @@ pub struct SkillsWindow {
     pub skill_rows: SkillRows,
     pub local_player_uid: f64,
     pub top_value: f64,
+    pub summon_value: f64, // part of the inspected player's total that came from their summons
+    pub passive_rows: PassiveRows,
+}
+
+pub type PassiveRows = Vec<PassiveRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PassiveRow {
+    pub uid: f64, // skill id of the passive
+    pub name: String,
+    pub procs: f64,
+    pub procs_per_minute: f64,
+    // Hits linked to the passive, already counted in the skill rows
+    pub total_value: f64,
+    pub value_pct: f64,
+    pub hits: f64,
 }
 
 pub type SkillRows = Vec<SkillRow>;
@@ -57,6 +94,12 @@ pub type SkillRows = Vec<SkillRow>;
 pub struct SkillRow {
     pub uid: f64,
     pub name: String,
+    pub is_summon: bool, // hits by the player's summons (pets, turrets, ...)
+    pub summon_uid: f64, // the summon that dealt every hit of the row, -1 for own hits or several summons
+    pub icon: String,    // "" if unknown
+    pub class_name: String,
+    pub class_spec_name: String, // Unknown Spec for skills shared by both specs
+    pub category: SkillCategory,
     // Stats
     pub total_value: f64,
     pub value_per_sec: f64,
@@ -65,6 +108,393 @@ pub struct SkillRow {
     pub crit_value_rate: f64,
     pub lucky_rate: f64,
     pub lucky_value_rate: f64,
+    pub rainbow_rate: f64,
     pub hits: f64,
     pub hits_per_minute: f64,
+    pub active_dps: f64,     // value per second of active time
+    pub active_time_ms: f64, // time spent hitting, gaps over the threshold excluded
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BuffsWindow {
+    pub entity_rows: BuffEntityRows,
+    pub local_player_uid: f64,
+}
+
+pub type BuffEntityRows = Vec<BuffEntityRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BuffEntityRow {
+    pub uid: f64,
+    pub name: String,
+    pub is_boss: bool,
+    pub buff_rows: BuffRows,
+}
+
+pub type BuffRows = Vec<BuffRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BuffRow {
+    pub base_id: f64,
+    pub name: String,
+    pub is_active: bool,
+    pub uptime_ms: f64,
+    pub uptime_pct: f64,
+    pub applications: f64,
+    pub refreshes: f64,
+    pub removals: f64,
+    pub curr_layer: f64,
+    pub max_layer: f64,
+    pub source_uids: Vec<f64>,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeathsWindow {
+    pub death_rows: DeathRows,
+    pub local_player_uid: f64,
+}
+
+pub type DeathRows = Vec<DeathRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeathRow {
+    pub player_uid: f64,
+    pub player_name: String,
+    pub timestamp_ms: f64,
+    pub elapsed_ms: f64, // since fight start
+    pub killer_uid: f64,
+    pub killer_name: String,
+    pub killer_skill_name: String,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CooldownsWindow {
+    pub local_player_uid: f64, // cooldowns are only synced for the local player
+    pub elapsed_ms: f64,
+    pub total_casts: f64, // skills only, fight resources excluded
+    pub casts_per_minute: f64,
+    pub cooldown_rows: CooldownRows,
+}
+
+pub type CooldownRows = Vec<CooldownRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CooldownRow {
+    pub uid: f64, // skill uid, or resource id for fight resources
+    pub name: String,
+    pub is_fight_resource: bool,
+    pub casts: f64,
+    pub casts_per_minute: f64,
+    pub cooldown_ms: f64,   // of the latest cast
+    pub ready_time_ms: f64, // time spent off cooldown without being used
+    pub ready_pct: f64,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HatesWindow {
+    pub local_player_uid: f64, // hate lists are only synced for the local player
+    pub last_boss_pull_ms: f64,
+    pub hate_rows: HateRows,
+    pub flip_rows: HateFlipRows, // newest first
+}
+
+pub type HateRows = Vec<HateRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HateRow {
+    pub uid: f64,
+    pub name: String,
+    pub monster_id: f64, // -1 if unknown
+    pub is_boss: bool,
+    pub is_holding: bool, // the local player is currently on its hate list
+    pub pulls: f64,
+    pub drops: f64,
+    pub hold_time_ms: f64,
+    pub hold_pct: f64,
+}
+
+pub type HateFlipRows = Vec<HateFlipRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HateFlipRow {
+    pub timestamp_ms: f64,
+    pub elapsed_ms: f64, // since fight start
+    pub entity_uid: f64,
+    pub entity_name: String,
+    pub is_boss: bool,
+    pub is_gained: bool,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetsWindow {
+    pub target_rows: TargetRows,
+}
+
+pub type TargetRows = Vec<TargetRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetRow {
+    pub uid: f64,
+    pub name: String,
+    pub monster_id: f64, // -1 if unknown
+    pub is_boss: bool,
+    pub curr_hp: f64, // -1 if unknown
+    pub max_hp: f64,  // -1 if unknown
+    pub hp_pct: f64,
+    pub is_departed: bool, // died or left view
+    pub taken_value: f64,
+    pub taken_hits: f64,
+    pub taken_pct: f64, // of the encounter's damage
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BodyPartsWindow {
+    pub entity_rows: BodyPartEntityRows,
+}
+
+pub type BodyPartEntityRows = Vec<BodyPartEntityRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BodyPartEntityRow {
+    pub uid: f64,
+    pub name: String,
+    pub monster_id: f64, // -1 if unknown
+    pub is_boss: bool,
+    pub total_value: f64, // damage dealt to all its parts
+    pub part_rows: BodyPartRows,
+}
+
+pub type BodyPartRows = Vec<BodyPartRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BodyPartRow {
+    pub part_id: f64,
+    pub state: String, // Default, Injury or Dead
+    pub curr_hp: f64,  // -1 if unknown
+    pub max_hp: f64,   // -1 if unknown
+    pub hp_pct: f64,
+    pub total_value: f64,
+    pub hits: f64,
+    pub broken_ms: f64,                          // -1 if not broken
+    pub broken_elapsed_ms: f64,                  // since fight start, -1 if not broken
+    pub state_changes: Vec<BodyPartStateChange>, // oldest first
+    pub player_rows: BodyPartPlayerRows,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BodyPartStateChange {
+    pub timestamp_ms: f64,
+    pub elapsed_ms: f64, // since fight start
+    pub state: String,
+}
+
+pub type BodyPartPlayerRows = Vec<BodyPartPlayerRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BodyPartPlayerRow {
+    pub uid: f64,
+    pub name: String,
+    pub class_name: String,
+    pub class_spec_name: String,
+    pub total_value: f64,
+    pub value_pct: f64, // of the damage dealt to the part
+    pub hits: f64,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeathRecapWindow {
+    pub player_uid: f64,
+    pub player_name: String,
+    pub death_recaps: Vec<DeathRecap>,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeathRecap {
+    pub death: DeathRow,
+    pub incoming_rows: IncomingRows,
+}
+
+pub type IncomingRows = Vec<IncomingRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingRow {
+    pub timestamp_ms: f64,
+    pub ms_before_death: f64,
+    pub source_uid: f64,
+    pub source_name: String,
+    pub skill_uid: f64,
+    pub skill_name: String,
+    pub value: f64,
+    pub is_heal: bool,
+    pub is_crit: bool,
+    pub is_lucky: bool,
+    pub is_killing_blow: bool,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BossesWindow {
+    pub boss_rows: BossRows,
+}
+
+pub type BossRows = Vec<BossRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BossRow {
+    pub uid: f64,
+    pub monster_id: f64,
+    pub name: String,
+    pub curr_hp: f64,
+    pub max_hp: f64,
+    pub hp_pct: f64,
+    pub is_dead: bool,
+    pub recent_dps: f64,
+    pub projected_kill_ms: f64, // -1 if it can't be estimated yet
+    pub phase_rows: BossPhaseRows,
+    pub hp_timeline: HpPoints,
+}
+
+pub type BossPhaseRows = Vec<BossPhaseRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BossPhaseRow {
+    pub index: f64,
+    pub start_threshold_pct: f64,
+    pub start_ms: f64,
+    pub end_ms: f64, // -1 while ongoing
+    pub duration_ms: f64,
+}
+
+pub type HpPoints = Vec<HpPoint>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HpPoint {
+    pub timestamp_ms: f64,
+    pub hp_pct: f64,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineWindow {
+    pub start_ms: f64,
+    pub bucket_ms: f64,         // every point covers this much time
+    pub group_points: Vec<f64>, // value per second of all players combined
+    pub timeline_rows: TimelineRows,
+}
+
+pub type TimelineRows = Vec<TimelineRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineRow {
+    pub uid: f64,
+    pub name: String,
+    pub class_name: String,
+    pub class_spec_name: String,
+    pub total_value: f64,
+    pub points: Vec<f64>, // value per second in each bucket
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EncounterHistoryWindow {
+    pub encounter_rows: EncounterRows,
+}
+
+pub type EncounterRows = Vec<EncounterRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EncounterRow {
+    pub id: f64,
+    pub end_reason: String,
+    pub time_fight_start_ms: f64,
+    pub time_last_combat_packet_ms: f64,
+    pub elapsed_ms: f64,
+    pub total_dmg: f64,
+    pub total_dps: f64,
+    pub total_heal: f64,
+    pub player_count: f64,
+    pub boss_names: Vec<String>,
+    pub map_id: Option<f64>,
+    pub line_id: Option<f64>,
+    pub level_map_id: Option<f64>,
+    pub dungeon_guid: Option<String>,
+    pub local_player_name: String,
+    pub top_player_name: String,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryWindow {
+    pub encounter_rows: EncounterRows,
+    pub total_count: f64,
+    pub page: f64,
+    pub page_size: f64,
+}
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DungeonRunsWindow {
+    pub run_rows: DungeonRunRows, // current run first, then newest first
+}
+
+pub type DungeonRunRows = Vec<DungeonRunRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DungeonRunRow {
+    pub scene_uuid: f64,
+    pub is_active: bool,
+    pub map_id: Option<f64>,
+    pub level_map_id: Option<f64>,
+    pub dungeon_guid: Option<String>,
+    pub difficulty: f64, // -1 if unknown
+    pub state: String,
+    pub result: f64, // -1 if unknown
+    pub start_ms: f64,
+    pub end_ms: f64,            // -1 while active
+    pub clear_time_ms: f64,     // -1 if not cleared
+    pub pass_time: f64,         // settlement clear time in seconds, -1 if unknown
+    pub total_score: f64,       // -1 if unknown
+    pub master_mode_score: f64, // -1 if unknown
+    pub affix_ids: Vec<f64>,
+    pub player_rows: DungeonPlayerRows,
+    pub encounter_ids: Vec<f64>,
+    pub history_encounter_ids: Vec<f64>,
+}
+
+pub type DungeonPlayerRows = Vec<DungeonPlayerRow>;
+
+#[derive(specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DungeonPlayerRow {
+    pub uid: f64,
+    pub name: String,
+    pub class_name: String,
+    pub level: f64,         // -1 if unknown
+    pub ability_score: f64, // -1 if unknown
 }
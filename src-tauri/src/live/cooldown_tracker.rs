@@ -0,0 +1,227 @@
+use crate::live::opcodes_models::CombatStats;
+use blueprotobuf_lib::blueprotobuf;
+use log::debug;
+use std::collections::HashMap;
+
+/// A cooldown started by the local player, i.e. a skill cast or a fight resource use
+#[derive(Debug, Default, Clone)]
+pub struct CastEvent {
+    pub begin_ms: u128,    // server time the cooldown started
+    pub cooldown_ms: u128, // effective cooldown (after reductions)
+}
+
+/// Casts and cooldowns of the local player, only the server's deltas to ourselves carry them
+#[derive(Debug, Default, Clone)]
+pub struct CooldownTracker {
+    pub skill_uid_to_casts: HashMap<i32, Vec<CastEvent>>, // oldest first
+    pub res_id_to_casts: HashMap<i32, Vec<CastEvent>>,    // oldest first
+}
+
+impl CooldownTracker {
+    /// Keeps the last cast of every skill and resource, so a cooldown running into the next encounter still counts there and isn't taken for a new cast
+    pub fn carry_over(&self) -> Self {
+        Self {
+            skill_uid_to_casts: carry_over_last_casts(&self.skill_uid_to_casts),
+            res_id_to_casts: carry_over_last_casts(&self.res_id_to_casts),
+        }
+    }
+
+    pub fn process_skill_cds(&mut self, skill_cds: &[blueprotobuf::SkillCd]) {
+        for skill_cd in skill_cds {
+            let Some(skill_level_id) = skill_cd.skill_level_id else {
+                continue;
+            };
+            let skill_uid = get_skill_uid(skill_level_id);
+            let casts = self.skill_uid_to_casts.entry(skill_uid).or_default();
+            // Charge skills only restart their cooldown when the recharge does, casts in between aren't seen
+            if record_cast(
+                casts,
+                skill_cd.begin_time,
+                skill_cd.valid_cd_time.or(skill_cd.duration),
+            ) {
+                debug!("skill {skill_uid} cast, {} casts", casts.len());
+            }
+        }
+    }
+
+    pub fn process_fight_res_cds(&mut self, fight_res_cds: &[blueprotobuf::FightResCd]) {
+        for fight_res_cd in fight_res_cds {
+            let Some(res_id) = fight_res_cd.res_id else {
+                continue;
+            };
+            let casts = self.res_id_to_casts.entry(res_id).or_default();
+            if record_cast(
+                casts,
+                fight_res_cd.begin_time,
+                fight_res_cd.valid_cd_time.or(fight_res_cd.duration),
+            ) {
+                debug!("fight resource {res_id} used, {} uses", casts.len());
+            }
+        }
+    }
+}
+
+fn carry_over_last_casts(
+    id_to_casts: &HashMap<i32, Vec<CastEvent>>,
+) -> HashMap<i32, Vec<CastEvent>> {
+    id_to_casts
+        .iter()
+        .filter_map(|(&id, casts)| Some((id, vec![casts.last()?.clone()])))
+        .collect()
+}
+
+/// Cooldowns are resent with every delta until they change, so only a new begin time counts as a cast
+#[allow(clippy::cast_sign_loss)]
+fn record_cast(casts: &mut Vec<CastEvent>, begin_time: Option<i64>, cooldown: Option<i32>) -> bool {
+    let Some(begin_time) = begin_time.filter(|&begin_time| begin_time > 0) else {
+        return false;
+    };
+    let begin_ms = begin_time as u128;
+    let cooldown_ms = cooldown.unwrap_or_default().max(0) as u128;
+    match casts.last_mut() {
+        // Same cast, the cooldown may have been reduced since
+        Some(last_cast) if last_cast.begin_ms == begin_ms => {
+            last_cast.cooldown_ms = cooldown_ms;
+            false
+        }
+        _ => {
+            casts.push(CastEvent {
+                begin_ms,
+                cooldown_ms,
+            });
+            true
+        }
+    }
+}
+
+/// `skill_level_id` is the skill id followed by a two digit level, fall back to it as-is for unknown skills
+fn get_skill_uid(skill_level_id: i32) -> i32 {
+    let skill_uid = skill_level_id / 100;
+    if CombatStats::is_known_skill(skill_uid) && !CombatStats::is_known_skill(skill_level_id) {
+        skill_uid
+    } else {
+        skill_level_id
+    }
+}
+
+pub fn count_casts(casts: &[CastEvent], window_start_ms: u128, window_end_ms: u128) -> usize {
+    casts
+        .iter()
+        .filter(|cast| (window_start_ms..=window_end_ms).contains(&cast.begin_ms))
+        .count()
+}
+
+/// Time inside `[window_start_ms, window_end_ms]` the skill was off cooldown (ready but not used)
+pub fn ready_time_ms(casts: &[CastEvent], window_start_ms: u128, window_end_ms: u128) -> u128 {
+    let mut on_cooldown_ms = 0;
+    let mut covered_until_ms = window_start_ms;
+    for cast in casts {
+        let start = cast.begin_ms.max(covered_until_ms);
+        let end = (cast.begin_ms + cast.cooldown_ms).min(window_end_ms);
+        if end > start {
+            on_cooldown_ms += end - start;
+            covered_until_ms = end;
+        }
+    }
+    window_end_ms
+        .saturating_sub(window_start_ms)
+        .saturating_sub(on_cooldown_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::cooldown_tracker::{
+        CastEvent, CooldownTracker, count_casts, ready_time_ms, record_cast,
+    };
+
+    const SKILL_UID: i32 = 1714;
+
+    #[test]
+    fn test_record_cast() {
+        let mut casts = Vec::new();
+        assert!(!record_cast(&mut casts, None, Some(10_000)));
+        assert!(!record_cast(&mut casts, Some(0), Some(10_000)));
+        assert!(record_cast(&mut casts, Some(1_000), Some(10_000)));
+
+        // Resent with every delta, a reduction only updates the cooldown
+        assert!(!record_cast(&mut casts, Some(1_000), Some(10_000)));
+        assert!(!record_cast(&mut casts, Some(1_000), Some(8_000)));
+        assert_eq!(casts.len(), 1);
+        assert_eq!(casts[0].cooldown_ms, 8_000);
+
+        assert!(record_cast(&mut casts, Some(9_000), None));
+        assert_eq!(casts.len(), 2);
+        assert_eq!(casts[1].cooldown_ms, 0);
+    }
+
+    #[test]
+    fn test_count_casts_in_window() {
+        let casts: Vec<CastEvent> = [1_000, 5_000, 9_000]
+            .into_iter()
+            .map(|begin_ms| CastEvent {
+                begin_ms,
+                cooldown_ms: 2_000,
+            })
+            .collect();
+        assert_eq!(count_casts(&casts, 0, 10_000), 3);
+        // Both window ends are inclusive
+        assert_eq!(count_casts(&casts, 5_000, 9_000), 2);
+        assert_eq!(count_casts(&casts, 5_001, 8_999), 0);
+    }
+
+    #[test]
+    fn test_ready_time_overlapping_casts() {
+        // Charge skills: the next charge is used while the first recharge is still running
+        let casts = vec![
+            CastEvent {
+                begin_ms: 1_000,
+                cooldown_ms: 4_000,
+            },
+            CastEvent {
+                begin_ms: 3_000,
+                cooldown_ms: 4_000,
+            },
+        ];
+        // On cooldown 1s..7s, the overlap only counts once
+        assert_eq!(ready_time_ms(&casts, 0, 10_000), 4_000);
+    }
+
+    #[test]
+    fn test_ready_time_clipped_to_window() {
+        let casts = vec![
+            CastEvent {
+                begin_ms: 0,
+                cooldown_ms: 4_000,
+            },
+            CastEvent {
+                begin_ms: 8_000,
+                cooldown_ms: 4_000,
+            },
+        ];
+        // Only 2s..4s and 8s..10s of the cooldowns are inside the window
+        assert_eq!(ready_time_ms(&casts, 2_000, 10_000), 4_000);
+        assert_eq!(ready_time_ms(&[], 2_000, 10_000), 8_000);
+    }
+
+    #[test]
+    fn test_last_cast_carries_over() {
+        let mut cooldown_tracker = CooldownTracker::default();
+        let casts = cooldown_tracker
+            .skill_uid_to_casts
+            .entry(SKILL_UID)
+            .or_default();
+        record_cast(casts, Some(1_000), Some(10_000));
+        record_cast(casts, Some(12_000), Some(10_000));
+
+        let mut next_cooldown_tracker = cooldown_tracker.carry_over();
+        let casts = next_cooldown_tracker
+            .skill_uid_to_casts
+            .get_mut(&SKILL_UID)
+            .unwrap();
+        assert_eq!(casts.len(), 1);
+        // The next encounter starts mid cooldown, the resent cooldown isn't a new cast
+        assert!(!record_cast(casts, Some(12_000), Some(10_000)));
+        assert_eq!(count_casts(casts, 15_000, 30_000), 0);
+        assert_eq!(ready_time_ms(casts, 15_000, 30_000), 8_000);
+    }
+}
@@ -0,0 +1,122 @@
+use blueprotobuf_lib::blueprotobuf;
+use blueprotobuf_lib::blueprotobuf::EDamageMode;
+
+// type_flag bits, from testing
+const CRIT_BIT: i32 = 0b00_00_00_01; // 1st bit, SyncDamageInfo.is_crit isn't reliable
+// Every other bit is unverified, e.g. the 3rd bit might mean the hit caused a lucky strike. They're only counted as unknown until captures with known outcomes confirm them
+const KNOWN_TYPE_FLAG_BITS: i32 = CRIT_BIT;
+
+/// Everything `SyncDamageInfo` says about how a hit landed, decoded once per hit
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DamageFlags {
+    pub type_flag: i32, // raw, for the bits that aren't understood yet
+    pub is_crit: bool,
+    pub is_lucky: bool, // the value comes in lucky_value instead of value
+    pub is_rainbow: bool,
+    pub is_normal: bool,
+    pub damage_mode: EDamageMode,
+    pub hit_event_id: Option<i32>, // shared by the hits of a single multi-hit event
+}
+
+impl DamageFlags {
+    pub fn decode(sync_damage_info: &blueprotobuf::SyncDamageInfo) -> Self {
+        Self::from_parts(
+            sync_damage_info.type_flag.unwrap_or_default(),
+            sync_damage_info.lucky_value.is_some(),
+            sync_damage_info.is_rainbow.unwrap_or_default(),
+            sync_damage_info.is_normal.unwrap_or_default(),
+            sync_damage_info.damage_mode.unwrap_or_default(),
+            sync_damage_info.hit_event_id,
+        )
+    }
+
+    /// Same as `decode`, from fields kept outside of the packet (e.g. the event log)
+    pub fn from_parts(
+        type_flag: i32,
+        is_lucky: bool,
+        is_rainbow: bool,
+        is_normal: bool,
+        damage_mode: i32,
+        hit_event_id: Option<i32>,
+    ) -> Self {
+        Self {
+            type_flag,
+            is_crit: (type_flag & CRIT_BIT) != 0,
+            is_lucky,
+            is_rainbow,
+            is_normal,
+            damage_mode: EDamageMode::try_from(damage_mode).unwrap_or_default(),
+            hit_event_id,
+        }
+    }
+
+    /// `type_flag` bits whose meaning isn't verified
+    pub fn unknown_type_flag_bits(&self) -> i32 {
+        self.type_flag & !KNOWN_TYPE_FLAG_BITS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::damage_flags::DamageFlags;
+    use blueprotobuf_lib::blueprotobuf::SyncDamageInfo;
+    use prost::Message;
+    use std::fs;
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct SyncDamageInfoFixture {
+        description: String,
+        bytes: Vec<u8>,
+        is_crit: bool,
+        is_lucky: bool,
+        is_rainbow: bool,
+        unknown_type_flag_bits: i32,
+    }
+
+    #[test]
+    fn test_decode_fixtures() {
+        // Synthetic SyncDamageInfo hits encoded from the bit layout above, they check the wire decoding but can't
+        // confirm what the bits mean. Captured hits with known outcomes belong here once there are some
+        let filename = "src/live/test_sync_damage_infos.json";
+        let fixtures: Vec<SyncDamageInfoFixture> = serde_json::from_str(
+            &fs::read_to_string(filename).unwrap_or_else(|_| panic!("Failed to open {filename}")),
+        )
+        .expect("Invalid JSON in test_sync_damage_infos.json");
+        assert!(!fixtures.is_empty());
+        for fixture in fixtures {
+            let sync_damage_info = SyncDamageInfo::decode(fixture.bytes.as_slice())
+                .unwrap_or_else(|e| panic!("{}: {e}", fixture.description));
+            let flags = DamageFlags::decode(&sync_damage_info);
+            assert_eq!(flags.is_crit, fixture.is_crit, "{}", fixture.description);
+            assert_eq!(flags.is_lucky, fixture.is_lucky, "{}", fixture.description);
+            assert_eq!(
+                flags.is_rainbow, fixture.is_rainbow,
+                "{}",
+                fixture.description
+            );
+            assert_eq!(
+                flags.unknown_type_flag_bits(),
+                fixture.unknown_type_flag_bits,
+                "{}",
+                fixture.description
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_missing_fields() {
+        let flags = DamageFlags::decode(&SyncDamageInfo {
+            is_normal: Some(true),
+            damage_mode: Some(99), // unknown mode
+            ..Default::default()
+        });
+        assert_eq!(
+            flags,
+            DamageFlags {
+                is_normal: true,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use log::info;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// How many incoming hits/heals are kept per player for death recaps
+pub const RECENT_INCOMING_CAPACITY: usize = 50;
+
+/// A single hit or heal received by a player
+#[derive(Debug, Default, Clone)]
+pub struct IncomingEvent {
+    pub timestamp_ms: u128,
+    pub source_uid: i64,
+    pub skill_uid: i32,
+    pub value: i64,
+    pub is_heal: bool,
+    pub is_crit: bool,
+    pub is_lucky: bool,
+    pub is_dead: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct DeathRecord {
+    pub player_uid: i64,
+    pub timestamp_ms: u128,
+    pub killer_uid: Option<i64>,
+    pub killer_skill_uid: Option<i32>,
+    pub recap: Vec<IncomingEvent>, // oldest first
+}
+
+/// Tracks deaths per player along with the hits and heals leading up to them
+#[derive(Debug, Default, Clone)]
+pub struct DeathTracker {
+    player_uid_to_recent_incoming: HashMap<i64, VecDeque<IncomingEvent>>,
+    dead_player_uids: HashSet<i64>,
+    pub deaths: Vec<DeathRecord>,
+}
+
+impl DeathTracker {
+    pub fn record_incoming(&mut self, player_uid: i64, incoming_event: IncomingEvent) {
+        let recent_incoming = self
+            .player_uid_to_recent_incoming
+            .entry(player_uid)
+            .or_default();
+        if recent_incoming.len() >= RECENT_INCOMING_CAPACITY {
+            recent_incoming.pop_front();
+        }
+        recent_incoming.push_back(incoming_event);
+    }
+
+    /// Records a death unless the player is already known to be dead, returns the new record.
+    /// Without an explicit killer, the source of the last incoming hit is blamed.
+    pub fn on_death(
+        &mut self,
+        player_uid: i64,
+        timestamp_ms: u128,
+        killer: Option<(i64, i32)>,
+    ) -> Option<&DeathRecord> {
+        if !self.dead_player_uids.insert(player_uid) {
+            return None;
+        }
+        let recap: Vec<IncomingEvent> = self
+            .player_uid_to_recent_incoming
+            .remove(&player_uid)
+            .map(Vec::from)
+            .unwrap_or_default();
+        let killer = killer.or_else(|| {
+            recap
+                .iter()
+                .rev()
+                .find(|incoming_event| !incoming_event.is_heal)
+                .map(|incoming_event| (incoming_event.source_uid, incoming_event.skill_uid))
+        });
+        info!("player {player_uid} died (killer: {killer:?})");
+        self.deaths.push(DeathRecord {
+            player_uid,
+            timestamp_ms,
+            killer_uid: killer.map(|(killer_uid, _)| killer_uid),
+            killer_skill_uid: killer.map(|(_, killer_skill_uid)| killer_skill_uid),
+            recap,
+        });
+        self.deaths.last()
+    }
+
+    pub fn on_revive(&mut self, player_uid: i64) {
+        self.dead_player_uids.remove(&player_uid);
+    }
+
+    pub fn get_deaths(&self, player_uid: i64) -> impl Iterator<Item = &DeathRecord> {
+        self.deaths
+            .iter()
+            .filter(move |death| death.player_uid == player_uid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::death_tracker::{DeathTracker, IncomingEvent, RECENT_INCOMING_CAPACITY};
+
+    const PLAYER_UID: i64 = 1234;
+    const BOSS_UID: i64 = 42;
+    const ADD_UID: i64 = 43;
+    const HEALER_UID: i64 = 1235;
+
+    fn incoming(
+        timestamp_ms: u128,
+        source_uid: i64,
+        skill_uid: i32,
+        is_heal: bool,
+    ) -> IncomingEvent {
+        IncomingEvent {
+            timestamp_ms,
+            source_uid,
+            skill_uid,
+            value: 100,
+            is_heal,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_killer_from_killing_blow() {
+        let mut death_tracker = DeathTracker::default();
+        death_tracker.record_incoming(PLAYER_UID, incoming(1_000, ADD_UID, 2, false));
+        let death = death_tracker
+            .on_death(PLAYER_UID, 2_000, Some((BOSS_UID, 1)))
+            .unwrap();
+        assert_eq!(death.killer_uid, Some(BOSS_UID));
+        assert_eq!(death.killer_skill_uid, Some(1));
+    }
+
+    #[test]
+    fn test_killer_from_last_hit() {
+        let mut death_tracker = DeathTracker::default();
+        death_tracker.record_incoming(PLAYER_UID, incoming(1_000, BOSS_UID, 1, false));
+        death_tracker.record_incoming(PLAYER_UID, incoming(1_500, ADD_UID, 2, false));
+        death_tracker.record_incoming(PLAYER_UID, incoming(1_800, HEALER_UID, 3, true));
+        // No killing blow seen, the last hit (not heal) is blamed
+        let death = death_tracker.on_death(PLAYER_UID, 2_000, None).unwrap();
+        assert_eq!(death.killer_uid, Some(ADD_UID));
+        assert_eq!(death.killer_skill_uid, Some(2));
+    }
+
+    #[test]
+    fn test_recap() {
+        let mut death_tracker = DeathTracker::default();
+        let total = RECENT_INCOMING_CAPACITY + 5;
+        for i in 0..total {
+            let is_heal = i % 10 == 0;
+            death_tracker.record_incoming(PLAYER_UID, incoming(i as u128, BOSS_UID, 1, is_heal));
+        }
+        death_tracker.record_incoming(HEALER_UID, incoming(0, BOSS_UID, 1, false));
+
+        let death = death_tracker.on_death(PLAYER_UID, 10_000, None).unwrap();
+        // Only the most recent hits and heals of that player, oldest first
+        assert_eq!(death.recap.len(), RECENT_INCOMING_CAPACITY);
+        assert_eq!(death.recap[0].timestamp_ms, 5);
+        assert_eq!(death.recap.last().unwrap().timestamp_ms, total as u128 - 1);
+        assert!(
+            death
+                .recap
+                .iter()
+                .any(|incoming_event| incoming_event.is_heal)
+        );
+
+        // The recap starts over for the next death
+        death_tracker.on_revive(PLAYER_UID);
+        let death = death_tracker.on_death(PLAYER_UID, 20_000, None).unwrap();
+        assert!(death.recap.is_empty());
+    }
+
+    #[test]
+    fn test_revive() {
+        let mut death_tracker = DeathTracker::default();
+        assert!(death_tracker.on_death(PLAYER_UID, 1_000, None).is_some());
+        // Still dead, e.g. the HP update after the killing blow
+        assert!(death_tracker.on_death(PLAYER_UID, 1_100, None).is_none());
+        death_tracker.on_revive(PLAYER_UID);
+        assert!(death_tracker.on_death(PLAYER_UID, 5_000, None).is_some());
+        assert_eq!(death_tracker.get_deaths(PLAYER_UID).count(), 2);
+        assert_eq!(death_tracker.get_deaths(HEALER_UID).count(), 0);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::live::location_tracker::Location;
+use blueprotobuf_lib::blueprotobuf;
+use blueprotobuf_lib::blueprotobuf::EDungeonState;
+use log::info;
+use std::collections::{HashMap, VecDeque};
+
+pub const MAX_DUNGEON_RUN_HISTORY: usize = 10;
+
+#[derive(Debug, Default, Clone)]
+pub struct DungeonPlayer {
+    pub name: Option<String>,
+    pub profession_id: Option<i32>,
+    pub level: Option<i32>,
+    pub ability_score: Option<i64>,
+}
+
+/// One dungeon instance from entering to leaving, spans every encounter fought inside
+#[derive(Debug, Default, Clone)]
+pub struct DungeonRun {
+    pub scene_uuid: i64,
+    pub location: Option<Location>,
+    pub difficulty: Option<i32>,
+    pub start_ms: u128,
+    pub end_ms: Option<u128>, // set when leaving the dungeon
+    pub state: EDungeonState,
+    pub state_changes: Vec<(u128, EDungeonState)>, // oldest first
+    pub result: Option<i32>,                       // raw DungeonFlowInfo.result
+    pub affix_ids: Vec<u32>,
+    pub pass_time: Option<i32>, // clear time from the settlement, in seconds
+    pub total_score: Option<i32>,
+    pub master_mode_score: Option<i32>,
+    pub uid_to_player: HashMap<i64, DungeonPlayer>,
+    pub encounter_ids: Vec<u32>, // EncounterManager ids, oldest first
+    pub history_encounter_ids: Vec<i64>, // history db ids of the same encounters
+}
+
+impl DungeonRun {
+    fn first_state_ms(&self, state: EDungeonState) -> Option<u128> {
+        self.state_changes
+            .iter()
+            .find(|(_, state_change)| *state_change == state)
+            .map(|&(timestamp_ms, _)| timestamp_ms)
+    }
+
+    /// From the start of the playing phase to its end, as seen by the meter
+    pub fn clear_time_ms(&self) -> Option<u128> {
+        let playing_ms = self.first_state_ms(EDungeonState::DungeonStatePlaying)?;
+        let end_ms = self.first_state_ms(EDungeonState::DungeonStateEnd)?;
+        Some(end_ms.saturating_sub(playing_ms))
+    }
+}
+
+/// Current dungeon run and the last few completed ones
+#[derive(Debug, Default, Clone)]
+pub struct DungeonTracker {
+    pub current_run: Option<DungeonRun>,
+    pub completed_runs: VecDeque<DungeonRun>, // oldest first
+}
+
+impl DungeonTracker {
+    pub fn process_dungeon_sync_data(
+        &mut self,
+        dungeon_sync_data: blueprotobuf::DungeonSyncData,
+        location: Option<&Location>,
+        timestamp_ms: u128,
+    ) -> Option<()> {
+        let scene_uuid = dungeon_sync_data.scene_uuid?;
+        if self
+            .current_run
+            .as_ref()
+            .is_some_and(|current_run| current_run.scene_uuid != scene_uuid)
+        {
+            self.end_run(timestamp_ms);
+        }
+        let dungeon_run = self.current_run.get_or_insert_with(|| {
+            info!("dungeon run started (scene {scene_uuid})");
+            DungeonRun {
+                scene_uuid,
+                start_ms: timestamp_ms,
+                ..Default::default()
+            }
+        });
+        if dungeon_run.location.is_none() {
+            dungeon_run.location = location.cloned();
+        }
+
+        if let Some(flow_info) = dungeon_sync_data.flow_info {
+            if let Some(state) = flow_info
+                .state
+                .and_then(|state| EDungeonState::try_from(state).ok())
+            {
+                if state != dungeon_run.state {
+                    info!(
+                        "dungeon {scene_uuid}: {} -> {}",
+                        dungeon_run.state.as_str_name(),
+                        state.as_str_name()
+                    );
+                    dungeon_run.state = state;
+                    dungeon_run.state_changes.push((timestamp_ms, state));
+                }
+            }
+            dungeon_run.result = flow_info.result.or(dungeon_run.result);
+        }
+        if let Some(dungeon_scene_info) = dungeon_sync_data.dungeon_scene_info {
+            dungeon_run.difficulty = dungeon_scene_info.difficulty.or(dungeon_run.difficulty);
+        }
+        if let Some(dungeon_affix_data) = dungeon_sync_data.dungeon_affix_data {
+            dungeon_run.affix_ids = dungeon_affix_data.affix_data;
+        }
+        if let Some(settlement) = dungeon_sync_data.settlement {
+            dungeon_run.pass_time = settlement.pass_time.or(dungeon_run.pass_time);
+            dungeon_run.master_mode_score = settlement
+                .master_mode_score
+                .or(dungeon_run.master_mode_score);
+        }
+        if let Some(dungeon_score) = dungeon_sync_data.dungeon_score {
+            dungeon_run.total_score = dungeon_score.total_score.or(dungeon_run.total_score);
+        }
+        if let Some(dungeon_player_list) = dungeon_sync_data.dungeon_player_list {
+            for player_info in dungeon_player_list.player_infos.into_values() {
+                let Some(social_data) = player_info.social_data else {
+                    continue;
+                };
+                let Some(uid) = player_info.char_id.or(social_data.char_id) else {
+                    continue;
+                };
+                let player = dungeon_run.uid_to_player.entry(uid).or_default();
+                if let Some(basic_data) = social_data.basic_data {
+                    player.name = basic_data.name.or(player.name.take());
+                    player.level = basic_data.level.or(player.level);
+                }
+                if let Some(profession_data) = social_data.profession_data {
+                    player.profession_id = profession_data.profession_id.or(player.profession_id);
+                }
+                if let Some(user_attr_data) = social_data.user_attr_data {
+                    player.ability_score = user_attr_data.fight_point.or(player.ability_score);
+                }
+            }
+        }
+        Some(())
+    }
+
+    /// Attributes a completed encounter to the current run, if any
+    pub fn record_encounter(&mut self, encounter_id: u32) {
+        let Some(dungeon_run) = &mut self.current_run else {
+            return;
+        };
+        dungeon_run.encounter_ids.push(encounter_id);
+    }
+
+    /// Encounters are saved to the history db in the background, the run may have ended by then
+    pub fn record_history_encounter(&mut self, encounter_id: u32, history_encounter_id: i64) {
+        if let Some(dungeon_run) = self
+            .current_run
+            .iter_mut()
+            .chain(self.completed_runs.iter_mut())
+            .find(|dungeon_run| dungeon_run.encounter_ids.contains(&encounter_id))
+        {
+            dungeon_run.history_encounter_ids.push(history_encounter_id);
+        }
+    }
+
+    /// Leaving the dungeon (server change) ends the run
+    pub fn end_run(&mut self, timestamp_ms: u128) {
+        let Some(mut dungeon_run) = self.current_run.take() else {
+            return;
+        };
+        info!(
+            "dungeon run ended (scene {}), {} encounters",
+            dungeon_run.scene_uuid,
+            dungeon_run.encounter_ids.len()
+        );
+        dungeon_run.end_ms = Some(timestamp_ms);
+        if self.completed_runs.len() >= MAX_DUNGEON_RUN_HISTORY {
+            self.completed_runs.pop_front();
+        }
+        self.completed_runs.push_back(dungeon_run);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::dungeon_tracker::DungeonTracker;
+    use blueprotobuf_lib::blueprotobuf::{
+        DungeonFlowInfo, DungeonSettlement, DungeonSyncData, EDungeonState,
+    };
+
+    const SCENE_UUID: i64 = 1_001;
+    const NEXT_SCENE_UUID: i64 = 1_002;
+
+    fn dungeon_sync_data(scene_uuid: i64, state: Option<EDungeonState>) -> DungeonSyncData {
+        DungeonSyncData {
+            scene_uuid: Some(scene_uuid),
+            flow_info: state.map(|state| DungeonFlowInfo {
+                state: Some(state as i32),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_clear_time_ms() {
+        let mut dungeon_tracker = DungeonTracker::default();
+        for (timestamp_ms, state) in [
+            (1_000, EDungeonState::DungeonStateReady),
+            (5_000, EDungeonState::DungeonStatePlaying),
+            (6_000, EDungeonState::DungeonStatePlaying), // resent, no change
+            (95_000, EDungeonState::DungeonStateEnd),
+        ] {
+            dungeon_tracker.process_dungeon_sync_data(
+                dungeon_sync_data(SCENE_UUID, Some(state)),
+                None,
+                timestamp_ms,
+            );
+        }
+        let mut settlement_data = dungeon_sync_data(SCENE_UUID, None);
+        settlement_data.settlement = Some(DungeonSettlement {
+            pass_time: Some(90),
+            ..Default::default()
+        });
+        dungeon_tracker.process_dungeon_sync_data(settlement_data, None, 96_000);
+
+        let dungeon_run = dungeon_tracker.current_run.as_ref().unwrap();
+        assert_eq!(dungeon_run.start_ms, 1_000);
+        assert_eq!(dungeon_run.state_changes.len(), 3);
+        assert_eq!(dungeon_run.clear_time_ms(), Some(90_000));
+        assert_eq!(dungeon_run.pass_time, Some(90));
+    }
+
+    #[test]
+    fn test_clear_time_needs_end() {
+        let mut dungeon_tracker = DungeonTracker::default();
+        dungeon_tracker.process_dungeon_sync_data(
+            dungeon_sync_data(SCENE_UUID, Some(EDungeonState::DungeonStatePlaying)),
+            None,
+            1_000,
+        );
+        let dungeon_run = dungeon_tracker.current_run.as_ref().unwrap();
+        assert_eq!(dungeon_run.clear_time_ms(), None);
+    }
+
+    #[test]
+    fn test_scene_change_ends_run() {
+        let mut dungeon_tracker = DungeonTracker::default();
+        assert!(
+            dungeon_tracker
+                .process_dungeon_sync_data(DungeonSyncData::default(), None, 1_000)
+                .is_none()
+        );
+        assert!(dungeon_tracker.current_run.is_none());
+
+        dungeon_tracker.process_dungeon_sync_data(dungeon_sync_data(SCENE_UUID, None), None, 1_000);
+        dungeon_tracker.record_encounter(1);
+        dungeon_tracker.process_dungeon_sync_data(
+            dungeon_sync_data(NEXT_SCENE_UUID, None),
+            None,
+            9_000,
+        );
+
+        let completed_run = dungeon_tracker.completed_runs.back().unwrap();
+        assert_eq!(completed_run.scene_uuid, SCENE_UUID);
+        assert_eq!(completed_run.end_ms, Some(9_000));
+        assert_eq!(completed_run.encounter_ids, vec![1]);
+        let current_run = dungeon_tracker.current_run.as_ref().unwrap();
+        assert_eq!(current_run.scene_uuid, NEXT_SCENE_UUID);
+        assert_eq!(current_run.start_ms, 9_000);
+
+        // Saved after the run ended, still attributed to it
+        dungeon_tracker.record_history_encounter(1, 77);
+        assert_eq!(
+            dungeon_tracker
+                .completed_runs
+                .back()
+                .unwrap()
+                .history_encounter_ids,
+            vec![77]
+        );
+
+        dungeon_tracker.end_run(12_000);
+        assert!(dungeon_tracker.current_run.is_none());
+        assert_eq!(dungeon_tracker.completed_runs.len(), 2);
+    }
+}
@@ -0,0 +1,367 @@
+use crate::history::db::HistoryDbMutex;
+use crate::history::writer::HistoryWriter;
+use crate::live::boss_tracker::BOSS_INACTIVE_GRACE_MS;
+use crate::live::dungeon_tracker::DungeonTracker;
+use crate::live::event_log::EventLog;
+use crate::live::opcodes_models::{Encounter, Entity};
+use log::info;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+// Matches the default of the "Reset after Elapsed Time" setting
+pub const DEFAULT_INACTIVITY_TIMEOUT_MS: u128 = 60_000;
+pub const MAX_ENCOUNTER_HISTORY: usize = 20;
+
+pub type EncounterManagerMutex = Arc<Mutex<EncounterManager>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncounterEndReason {
+    Inactivity,
+    BossDeath,
+    Reset,
+    ServerChange,
+}
+
+impl EncounterEndReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EncounterEndReason::Inactivity => "inactivity",
+            EncounterEndReason::BossDeath => "bossDeath",
+            EncounterEndReason::Reset => "reset",
+            EncounterEndReason::ServerChange => "serverChange",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompletedEncounter {
+    pub id: u32,
+    pub end_reason: EncounterEndReason,
+    pub encounter: Arc<Encounter>, // shared with the history writer, without its event log
+}
+
+/// Splits the live encounter into segments and keeps the last few completed ones around
+#[derive(Debug)]
+pub struct EncounterManager {
+    pub inactivity_timeout_ms: u128, // 0 = never time out
+    next_id: u32,
+    pub history: VecDeque<CompletedEncounter>, // oldest first
+    pub last_event_log: Option<(u32, Arc<EventLog>)>, // only the last completed encounter keeps its event log
+    history_writer: Option<HistoryWriter>, // completed encounters are also saved to the history db
+    pub dungeon_tracker: DungeonTracker,   // runs span several encounters
+}
+
+impl Default for EncounterManager {
+    fn default() -> Self {
+        Self {
+            inactivity_timeout_ms: DEFAULT_INACTIVITY_TIMEOUT_MS,
+            next_id: 1,
+            history: VecDeque::new(),
+            last_event_log: None,
+            history_writer: None,
+            dungeon_tracker: DungeonTracker::default(),
+        }
+    }
+}
+
+impl EncounterManager {
+    pub fn new(inactivity_timeout_ms: u128, history_db: Option<HistoryDbMutex>) -> Self {
+        Self {
+            inactivity_timeout_ms,
+            history_writer: history_db.map(HistoryWriter::spawn),
+            ..Default::default()
+        }
+    }
+
+    /// Checks whether the live encounter is over, called before processing new combat packets and periodically in between
+    pub fn should_close(
+        &self,
+        encounter: &Encounter,
+        timestamp_ms: u128,
+    ) -> Option<EncounterEndReason> {
+        if !has_combat(encounter) {
+            return None;
+        }
+        if encounter.boss_tracker.last_boss_death_ms.is_some()
+            && encounter
+                .boss_tracker
+                .are_engaged_bosses_down(timestamp_ms, BOSS_INACTIVE_GRACE_MS)
+        {
+            return Some(EncounterEndReason::BossDeath);
+        }
+        let inactive_ms = timestamp_ms.saturating_sub(encounter.time_last_combat_packet_ms);
+        if self.inactivity_timeout_ms > 0 && inactive_ms > self.inactivity_timeout_ms {
+            return Some(EncounterEndReason::Inactivity);
+        }
+        None
+    }
+
+    pub fn close_if_needed(&mut self, encounter: &mut Encounter, timestamp_ms: u128) {
+        self.record_saved_encounters();
+        if let Some(end_reason) = self.should_close(encounter, timestamp_ms) {
+            self.close_encounter(encounter, end_reason);
+        }
+    }
+
+    /// Moves the live encounter into the history and leaves a fresh one behind.
+    /// Entity identity (names, classes, monster ids, HP, departures) and who has the local player on their hate list, active buffs and the last cast of every skill carry over, combat data doesn't.
+    pub fn close_encounter(&mut self, encounter: &mut Encounter, end_reason: EncounterEndReason) {
+        let next_encounter = Encounter {
+            is_encounter_paused: encounter.is_encounter_paused,
+            now_ms: encounter.now_ms,
+            entity_uid_to_entity: encounter
+                .entity_uid_to_entity
+                .iter()
+                .map(|(&entity_uid, entity)| {
+                    let entity = Entity {
+                        entity_type: entity.entity_type,
+                        name: entity.name.clone(),
+                        class: entity.class,
+                        class_spec: entity.class_spec,
+                        class_spec_hits: entity.class_spec_hits.clone(),
+                        ability_score: entity.ability_score,
+                        monster_id: entity.monster_id,
+                        curr_hp: entity.curr_hp,
+                        max_hp: entity.max_hp,
+                        monster_pos: entity.monster_pos,
+                        departed_ms: entity.departed_ms,
+                        disappear_type: entity.disappear_type,
+                        ..Default::default()
+                    };
+                    (entity_uid, entity)
+                })
+                .collect(),
+            hate_tracker: encounter.hate_tracker.carry_over(encounter.now_ms),
+            buff_tracker: encounter.buff_tracker.carry_over(),
+            cooldown_tracker: encounter.cooldown_tracker.carry_over(),
+            local_player: encounter.local_player.clone(),
+            local_player_ms: encounter.local_player_ms,
+            location: encounter.location.clone(),
+            ..Default::default()
+        };
+        let mut completed_encounter = std::mem::replace(encounter, next_encounter);
+        if !has_combat(&completed_encounter) {
+            return;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        info!(
+            "encounter {id} closed ({}), lasted {}ms",
+            end_reason.as_str(),
+            completed_encounter.time_last_combat_packet_ms
+                - completed_encounter.time_fight_start_ms
+        );
+        // Event logs are by far the biggest part of an encounter, older ones aren't kept around
+        let event_log = std::mem::take(&mut completed_encounter.event_log);
+        self.last_event_log = Some((id, Arc::new(event_log)));
+        let completed_encounter = Arc::new(completed_encounter);
+        if let Some(history_writer) = &self.history_writer {
+            history_writer.save(id, completed_encounter.clone(), end_reason.as_str());
+        }
+        self.dungeon_tracker.record_encounter(id);
+        if self.history.len() >= MAX_ENCOUNTER_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(CompletedEncounter {
+            id,
+            end_reason,
+            encounter: completed_encounter,
+        });
+    }
+
+    /// Links encounters the history writer finished saving to their dungeon run
+    fn record_saved_encounters(&mut self) {
+        let Some(history_writer) = &self.history_writer else {
+            return;
+        };
+        for (id, history_encounter_id) in history_writer.take_saved() {
+            self.dungeon_tracker
+                .record_history_encounter(id, history_encounter_id);
+        }
+    }
+
+    pub fn get(&self, id: u32) -> Option<&CompletedEncounter> {
+        self.history
+            .iter()
+            .find(|completed_encounter| completed_encounter.id == id)
+    }
+}
+
+fn has_combat(encounter: &Encounter) -> bool {
+    encounter.time_fight_start_ms != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::encounter_manager::{EncounterEndReason, EncounterManager};
+    use crate::live::event_log::HitEvent;
+    use crate::live::opcodes_models::{Encounter, Entity};
+    use blueprotobuf_lib::blueprotobuf::EDisappearType;
+
+    const BOSS_UID: i64 = 42;
+
+    fn fought_encounter(time_fight_start_ms: u128, time_last_combat_packet_ms: u128) -> Encounter {
+        let mut encounter = Encounter {
+            time_fight_start_ms,
+            time_last_combat_packet_ms,
+            ..Default::default()
+        };
+        encounter.entity_uid_to_entity.insert(
+            BOSS_UID,
+            Entity {
+                name: Some("Boss".to_string()),
+                monster_id: Some(7),
+                ..Default::default()
+            },
+        );
+        encounter
+    }
+
+    #[test]
+    fn test_close_on_inactivity() {
+        let mut encounter_manager = EncounterManager::new(60_000, None);
+        let mut encounter = fought_encounter(1_000, 2_000);
+
+        encounter_manager.close_if_needed(&mut encounter, 62_000);
+        assert!(encounter_manager.history.is_empty());
+
+        encounter_manager.close_if_needed(&mut encounter, 62_001);
+        let completed_encounter = encounter_manager.history.back().unwrap();
+        assert_eq!(
+            completed_encounter.end_reason,
+            EncounterEndReason::Inactivity
+        );
+        assert_eq!(completed_encounter.encounter.time_fight_start_ms, 1_000);
+        assert_eq!(encounter.time_fight_start_ms, 0);
+        assert_eq!(
+            encounter.entity_uid_to_entity[&BOSS_UID].monster_id,
+            Some(7)
+        );
+
+        // Nothing to close until the next fight starts
+        encounter_manager.close_if_needed(&mut encounter, 200_000);
+        assert_eq!(encounter_manager.history.len(), 1);
+    }
+
+    #[test]
+    fn test_no_inactivity_timeout() {
+        let encounter_manager = EncounterManager::new(0, None);
+        let encounter = fought_encounter(1_000, 2_000);
+        assert_eq!(encounter_manager.should_close(&encounter, u128::MAX), None);
+    }
+
+    #[test]
+    fn test_close_on_boss_death() {
+        let mut encounter_manager = EncounterManager::new(60_000, None);
+        let mut encounter = fought_encounter(1_000, 5_000);
+        encounter
+            .boss_tracker
+            .record_hp(BOSS_UID, 1_000, 1_000, 1_000);
+        encounter
+            .boss_tracker
+            .record_hp(BOSS_UID, 300, 1_000, 3_000);
+        assert_eq!(encounter_manager.should_close(&encounter, 5_000), None);
+
+        encounter.boss_tracker.record_hp(BOSS_UID, 0, 1_000, 5_000);
+        encounter_manager.close_if_needed(&mut encounter, 5_000);
+        let completed_encounter = encounter_manager.history.back().unwrap();
+        assert_eq!(completed_encounter.id, 1);
+        assert_eq!(
+            completed_encounter.end_reason,
+            EncounterEndReason::BossDeath
+        );
+        assert!(encounter.boss_tracker.last_boss_death_ms.is_none());
+    }
+
+    #[test]
+    fn test_close_after_last_boss_death() {
+        const SECOND_BOSS_UID: i64 = 43;
+        let encounter_manager = EncounterManager::new(60_000, None);
+        let mut encounter = fought_encounter(1_000, 5_000);
+        for boss_uid in [BOSS_UID, SECOND_BOSS_UID] {
+            encounter
+                .boss_tracker
+                .record_hp(boss_uid, 1_000, 1_000, 1_000);
+            encounter
+                .boss_tracker
+                .record_hp(boss_uid, 500, 1_000, 3_000);
+        }
+
+        // The fight goes on while the second boss is still being hit
+        encounter.boss_tracker.record_hp(BOSS_UID, 0, 1_000, 4_000);
+        encounter
+            .boss_tracker
+            .record_hp(SECOND_BOSS_UID, 200, 1_000, 5_000);
+        assert_eq!(encounter_manager.should_close(&encounter, 5_000), None);
+
+        encounter
+            .boss_tracker
+            .record_hp(SECOND_BOSS_UID, 0, 1_000, 6_000);
+        assert_eq!(
+            encounter_manager.should_close(&encounter, 6_000),
+            Some(EncounterEndReason::BossDeath)
+        );
+    }
+
+    #[test]
+    fn test_departure_carries_over() {
+        let mut encounter_manager = EncounterManager::new(60_000, None);
+        let mut encounter = fought_encounter(1_000, 2_000);
+        let boss = encounter.entity_uid_to_entity.get_mut(&BOSS_UID).unwrap();
+        boss.departed_ms = Some(1_500);
+        boss.disappear_type = Some(EDisappearType::EDisappearDead);
+        encounter_manager.close_encounter(&mut encounter, EncounterEndReason::Reset);
+
+        // Still gone in the next encounter, and still expires from its departure time
+        let boss = &encounter.entity_uid_to_entity[&BOSS_UID];
+        assert_eq!(boss.departed_ms, Some(1_500));
+        assert_eq!(boss.disappear_type, Some(EDisappearType::EDisappearDead));
+    }
+
+    #[test]
+    fn test_haters_carry_over() {
+        let mut encounter_manager = EncounterManager::new(60_000, None);
+        let mut encounter = fought_encounter(1_000, 2_000);
+        let hate_uuids = [(BOSS_UID << 16) | 64];
+        encounter.hate_tracker.process_hate_ids(&hate_uuids, 1_000);
+        encounter.now_ms = 2_500;
+        encounter_manager.close_encounter(&mut encounter, EncounterEndReason::Reset);
+
+        // Still on the boss's hate list, that's no new pull but it's held from the close on
+        encounter.hate_tracker.process_hate_ids(&hate_uuids, 3_000);
+        assert!(encounter.hate_tracker.last_pull.is_none());
+        assert!(encounter.hate_tracker.recent_flips.is_empty());
+        let hate_stats = &encounter.hate_tracker.entity_uid_to_hate_stats[&BOSS_UID];
+        assert_eq!(hate_stats.pulls, 0);
+        assert!(hate_stats.is_holding());
+
+        encounter.hate_tracker.process_hate_ids(&[], 4_000);
+        let hate_stats = &encounter.hate_tracker.entity_uid_to_hate_stats[&BOSS_UID];
+        assert_eq!(hate_stats.drops, 1);
+        assert_eq!(hate_stats.hold_time_ms(3_000, 10_000), 1_000);
+    }
+    #[test]
+    fn test_only_last_event_log_kept() {
+        let mut encounter_manager = EncounterManager::new(60_000, None);
+        for id in 1..=2 {
+            let mut encounter = fought_encounter(1_000, 2_000);
+            encounter.event_log.record_hit(HitEvent::default());
+            encounter_manager.close_encounter(&mut encounter, EncounterEndReason::Reset);
+
+            let (last_id, event_log) = encounter_manager.last_event_log.as_ref().unwrap();
+            assert_eq!(*last_id, id);
+            assert_eq!(event_log.events.len(), 1);
+        }
+        assert!(
+            encounter_manager
+                .history
+                .iter()
+                .all(|completed_encounter| completed_encounter
+                    .encounter
+                    .event_log
+                    .events
+                    .is_empty())
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::live::damage_flags::DamageFlags;
+use crate::live::death_tracker::DeathRecord;
+use crate::live::opcodes_models::CombatStats;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// Dumps go to this folder in the app data dir
+pub const EVENT_LOGS_DIR: &str = "event_logs";
+
+// Oldest events are dropped past this, a long open world session would grow the log forever otherwise
+pub const MAX_EVENT_LOG_EVENTS: usize = 500_000;
+
+/// A single damage or heal instance, with enough of the raw packet to recompute any aggregate
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HitEvent {
+    pub timestamp_ms: u128,
+    pub attacker_uid: i64,        // owner for summon hits
+    pub direct_attacker_uid: i64, // the summon itself for summon hits
+    pub target_uid: i64,
+    pub skill_uid: i32,
+    pub value: i64,
+    pub is_heal: bool,
+    pub is_boss: bool,
+    pub is_dead: bool, // the hit killed the target
+    // Raw DamageFlags inputs
+    pub type_flag: i32,
+    pub is_lucky: bool,
+    pub is_rainbow: bool,
+    pub is_normal: bool,
+    pub damage_mode: i32,
+    pub hit_event_id: Option<i32>,
+    pub passive_uuid: Option<u32>,
+}
+
+impl HitEvent {
+    pub fn damage_flags(&self) -> DamageFlags {
+        DamageFlags::from_parts(
+            self.type_flag,
+            self.is_lucky,
+            self.is_rainbow,
+            self.is_normal,
+            self.damage_mode,
+            self.hit_event_id,
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuffEvent {
+    pub timestamp_ms: u128,
+    pub host_uid: i64,
+    pub source_uid: Option<i64>, // only known when applied
+    pub base_id: i32,
+    pub layer: i32,
+    pub is_gained: bool,
+}
+
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeathEvent {
+    pub timestamp_ms: u128,
+    pub player_uid: i64,
+    pub killer_uid: Option<i64>,
+    pub killer_skill_uid: Option<i32>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CombatEvent {
+    Hit(HitEvent),
+    Buff(BuffEvent),
+    Death(DeathEvent),
+}
+
+/// Every processed event of the encounter in arrival order, so new metrics can be computed after the fact from a dump.
+/// The live `CombatStats` are still folded as events arrive rather than derived from the log (the windows would replay
+/// the whole log on every refresh), `get_stats` replays it the same way and dumps check that both agree.
+#[derive(Debug, Default, Clone)]
+pub struct EventLog {
+    pub events: VecDeque<CombatEvent>,
+    pub dropped_events: usize, // past MAX_EVENT_LOG_EVENTS, replays no longer match the live stats
+}
+
+impl EventLog {
+    fn push(&mut self, event: CombatEvent) {
+        if self.events.len() >= MAX_EVENT_LOG_EVENTS {
+            self.events.pop_front();
+            self.dropped_events += 1;
+        }
+        self.events.push_back(event);
+    }
+
+    pub fn record_hit(&mut self, hit_event: HitEvent) {
+        self.push(CombatEvent::Hit(hit_event));
+    }
+
+    pub fn record_buff(&mut self, buff_event: BuffEvent) {
+        self.push(CombatEvent::Buff(buff_event));
+    }
+
+    pub fn record_death(&mut self, death: &DeathRecord) {
+        self.push(CombatEvent::Death(DeathEvent {
+            timestamp_ms: death.timestamp_ms,
+            player_uid: death.player_uid,
+            killer_uid: death.killer_uid,
+            killer_skill_uid: death.killer_skill_uid,
+        }));
+    }
+
+    pub fn hits(&self) -> impl Iterator<Item = &HitEvent> {
+        self.events.iter().filter_map(|event| match event {
+            CombatEvent::Hit(hit_event) => Some(hit_event),
+            _ => None,
+        })
+    }
+
+    /// Folds the hits matching `filter` into stats, same as they're aggregated live
+    pub fn get_stats(&self, filter: impl Fn(&HitEvent) -> bool) -> CombatStats {
+        let mut stats = CombatStats::default();
+        for hit_event in self.hits().filter(|&hit_event| filter(hit_event)) {
+            stats.add_hit(
+                hit_event.value,
+                &hit_event.damage_flags(),
+                hit_event.timestamp_ms,
+            );
+        }
+        stats
+    }
+
+    /// Writes the log as JSON lines, one event per line
+    pub fn dump(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        for event in &self.events {
+            serde_json::to_writer(&mut writer, event)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+}
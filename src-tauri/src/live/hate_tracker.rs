@@ -0,0 +1,192 @@
+use log::debug;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// How many aggro flips are kept for the hate window
+pub const RECENT_FLIPS_CAPACITY: usize = 50;
+
+/// The local player was put on or taken off an entity's hate list
+#[derive(Debug, Default, Clone)]
+pub struct HateFlip {
+    pub timestamp_ms: u128,
+    pub entity_uid: i64,
+    pub is_gained: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct HateStats {
+    pub pulls: i64, // times the local player got on the hate list
+    pub drops: i64,
+    // (start, end) of each period the local player was on the hate list, end is None while still on it
+    pub hold_intervals: Vec<(u128, Option<u128>)>,
+}
+
+impl HateStats {
+    pub fn is_holding(&self) -> bool {
+        matches!(self.hold_intervals.last(), Some((_, None)))
+    }
+
+    /// Time the local player was on the hate list inside `[window_start_ms, window_end_ms]`
+    pub fn hold_time_ms(&self, window_start_ms: u128, window_end_ms: u128) -> u128 {
+        self.hold_intervals
+            .iter()
+            .map(|&(start, end)| {
+                let start = start.max(window_start_ms);
+                let end = end.unwrap_or(window_end_ms).min(window_end_ms);
+                end.saturating_sub(start)
+            })
+            .sum()
+    }
+}
+
+/// Tracks which entities have the local player on their hate list, from `AoiSyncToMeDelta.sync_hate_ids`
+#[derive(Debug, Default, Clone)]
+pub struct HateTracker {
+    hater_uids: HashSet<i64>,
+    pub entity_uid_to_hate_stats: HashMap<i64, HateStats>,
+    pub recent_flips: VecDeque<HateFlip>, // oldest first
+    pub last_pull: Option<HateFlip>,
+}
+
+impl HateTracker {
+    /// `hate_uuids` is the full list of entities hating the local player, it's resent with every self delta
+    pub fn process_hate_ids(&mut self, hate_uuids: &[i64], timestamp_ms: u128) {
+        let hater_uids: HashSet<i64> = hate_uuids.iter().map(|hate_uuid| hate_uuid >> 16).collect();
+        let gained_uids: Vec<i64> = hater_uids.difference(&self.hater_uids).copied().collect();
+        let lost_uids: Vec<i64> = self.hater_uids.difference(&hater_uids).copied().collect();
+        for entity_uid in gained_uids {
+            let hate_stats = self.entity_uid_to_hate_stats.entry(entity_uid).or_default();
+            hate_stats.pulls += 1;
+            hate_stats.hold_intervals.push((timestamp_ms, None));
+            let flip = HateFlip {
+                timestamp_ms,
+                entity_uid,
+                is_gained: true,
+            };
+            self.last_pull = Some(flip.clone());
+            self.push_flip(flip);
+        }
+        for entity_uid in lost_uids {
+            let hate_stats = self.entity_uid_to_hate_stats.entry(entity_uid).or_default();
+            hate_stats.drops += 1;
+            if let Some((_, end @ None)) = hate_stats.hold_intervals.last_mut() {
+                *end = Some(timestamp_ms);
+            }
+            self.push_flip(HateFlip {
+                timestamp_ms,
+                entity_uid,
+                is_gained: false,
+            });
+        }
+        self.hater_uids = hater_uids;
+    }
+
+    /// What the next encounter starts from: who already hates the local player, so they don't count as new pulls
+    /// but are held from `start_ms` on
+    pub fn carry_over(&self, start_ms: u128) -> Self {
+        Self {
+            hater_uids: self.hater_uids.clone(),
+            entity_uid_to_hate_stats: self
+                .hater_uids
+                .iter()
+                .map(|&entity_uid| {
+                    let hate_stats = HateStats {
+                        hold_intervals: vec![(start_ms, None)],
+                        ..Default::default()
+                    };
+                    (entity_uid, hate_stats)
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn push_flip(&mut self, flip: HateFlip) {
+        debug!(
+            "{} hate of {}",
+            if flip.is_gained { "gained" } else { "lost" },
+            flip.entity_uid
+        );
+        if self.recent_flips.len() >= RECENT_FLIPS_CAPACITY {
+            self.recent_flips.pop_front();
+        }
+        self.recent_flips.push_back(flip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::hate_tracker::HateTracker;
+
+    const BOSS_UID: i64 = 42;
+    const ADD_UID: i64 = 43;
+
+    fn hate_uuid(entity_uid: i64) -> i64 {
+        (entity_uid << 16) | 64
+    }
+
+    #[test]
+    fn test_pull_and_drop() {
+        let mut hate_tracker = HateTracker::default();
+        hate_tracker.process_hate_ids(&[hate_uuid(BOSS_UID)], 1_000);
+        // Resent unchanged, nothing new
+        hate_tracker.process_hate_ids(&[hate_uuid(BOSS_UID)], 2_000);
+        hate_tracker.process_hate_ids(&[hate_uuid(BOSS_UID), hate_uuid(ADD_UID)], 3_000);
+        assert_eq!(hate_tracker.last_pull.as_ref().unwrap().entity_uid, ADD_UID);
+        hate_tracker.process_hate_ids(&[hate_uuid(ADD_UID)], 4_000);
+
+        let boss_hate_stats = &hate_tracker.entity_uid_to_hate_stats[&BOSS_UID];
+        assert_eq!((boss_hate_stats.pulls, boss_hate_stats.drops), (1, 1));
+        assert!(!boss_hate_stats.is_holding());
+        let add_hate_stats = &hate_tracker.entity_uid_to_hate_stats[&ADD_UID];
+        assert_eq!((add_hate_stats.pulls, add_hate_stats.drops), (1, 0));
+        assert!(add_hate_stats.is_holding());
+
+        let flips: Vec<(u128, i64, bool)> = hate_tracker
+            .recent_flips
+            .iter()
+            .map(|flip| (flip.timestamp_ms, flip.entity_uid, flip.is_gained))
+            .collect();
+        assert_eq!(
+            flips,
+            vec![
+                (1_000, BOSS_UID, true),
+                (3_000, ADD_UID, true),
+                (4_000, BOSS_UID, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hold_time() {
+        let mut hate_tracker = HateTracker::default();
+        hate_tracker.process_hate_ids(&[hate_uuid(BOSS_UID)], 1_000);
+        hate_tracker.process_hate_ids(&[], 3_000);
+        hate_tracker.process_hate_ids(&[hate_uuid(BOSS_UID)], 5_000);
+
+        let hate_stats = &hate_tracker.entity_uid_to_hate_stats[&BOSS_UID];
+        assert_eq!(
+            hate_stats.hold_intervals,
+            vec![(1_000, Some(3_000)), (5_000, None)]
+        );
+        // The open interval runs to the window end, both are clipped to the window
+        assert_eq!(hate_stats.hold_time_ms(0, 8_000), 5_000);
+        assert_eq!(hate_stats.hold_time_ms(2_000, 6_000), 2_000);
+        assert_eq!(hate_stats.hold_time_ms(3_500, 4_500), 0);
+    }
+
+    #[test]
+    fn test_carry_over_opens_hold_interval() {
+        let mut hate_tracker = HateTracker::default();
+        hate_tracker.process_hate_ids(&[hate_uuid(BOSS_UID)], 1_000);
+
+        let mut next_hate_tracker = hate_tracker.carry_over(10_000);
+        let hate_stats = &next_hate_tracker.entity_uid_to_hate_stats[&BOSS_UID];
+        assert_eq!(hate_stats.pulls, 0);
+        assert_eq!(hate_stats.hold_intervals, vec![(10_000, None)]);
+
+        next_hate_tracker.process_hate_ids(&[], 12_000);
+        let hate_stats = &next_hate_tracker.entity_uid_to_hate_stats[&BOSS_UID];
+        assert_eq!(hate_stats.drops, 1);
+        assert_eq!(hate_stats.hold_time_ms(10_000, 20_000), 2_000);
+    }
+}
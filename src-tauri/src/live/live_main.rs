@@ -1,4 +1,6 @@
 use crate::live::bptimer_state::{BPTimerEnabledMutex, is_bptimer_enabled};
+use crate::live::clock::{Clock, ServerClock, WallClock};
+use crate::live::encounter_manager::{EncounterEndReason, EncounterManagerMutex};
 use crate::live::opcodes_models::EncounterMutex;
 use crate::live::opcodes_process::{
     on_server_change, process_aoi_sync_delta, process_sync_container_data,
@@ -12,6 +14,9 @@ use log::{info, warn};
 use prost::Message;
 use tauri::{AppHandle, Manager};
 
+// How often the live encounter is checked for inactivity while no packets arrive
+const CLOSE_CHECK_INTERVAL_MS: u64 = 1_000;
+
 pub async fn start(app_handle: AppHandle) {
     // todo: add app_handle?
     // https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html
@@ -19,23 +24,61 @@ pub async fn start(app_handle: AppHandle) {
     let mut rx = packets::packet_capture::start_capture(); // Since live meter is not critical, it's ok to just log it // TODO: maybe bubble an error up to the frontend instead?
 
     let bptimer_enabled_state = app_handle.state::<BPTimerEnabledMutex>();
+    let encounter_manager_state = app_handle.state::<EncounterManagerMutex>();
+    let mut server_clock = ServerClock::default();
+    let mut close_check_interval =
+        tokio::time::interval(std::time::Duration::from_millis(CLOSE_CHECK_INTERVAL_MS));
 
     // 2. Use the channel to receive packets back and process them
-    while let Some((op, data)) = rx.recv().await {
+    loop {
+        let (op, data, captured_ms) = tokio::select! {
+            packet = rx.recv() => match packet {
+                Some(packet) => packet,
+                None => break,
+            },
+            _ = close_check_interval.tick() => {
+                // Without this an idle encounter would only be closed once the next fight starts
+                server_clock.set_local_ms(WallClock.now_ms());
+                let encounter_state = app_handle.state::<EncounterMutex>();
+                let mut encounter_state = encounter_state.lock().unwrap();
+                if !encounter_state.is_encounter_paused {
+                    encounter_state.now_ms = server_clock.now_ms();
+                    encounter_manager_state
+                        .lock()
+                        .unwrap()
+                        .close_if_needed(&mut encounter_state, server_clock.now_ms());
+                }
+                continue;
+            }
+        };
+        server_clock.set_local_ms(captured_ms);
         {
             let state = app_handle.state::<EncounterMutex>();
-            let encounter = state.lock().unwrap();
+            let mut encounter = state.lock().unwrap();
             if encounter.is_encounter_paused {
                 info!("packet dropped due to encounter paused");
                 continue;
             }
+            encounter.now_ms = server_clock.now_ms();
         }
         // error!("Received Pkt {op:?}");
         match op {
             packets::opcodes::Pkt::ServerChangeInfo => {
                 let encounter_state = app_handle.state::<EncounterMutex>();
                 let mut encounter_state = encounter_state.lock().unwrap();
+                let mut encounter_manager = encounter_manager_state.lock().unwrap();
+                encounter_manager
+                    .close_encounter(&mut encounter_state, EncounterEndReason::ServerChange);
+                encounter_manager
+                    .dungeon_tracker
+                    .end_run(server_clock.now_ms());
                 on_server_change(&mut encounter_state);
+                let player_state_mutex = app_handle.state::<PlayerStateMutex>();
+                player_state_mutex
+                    .lock()
+                    .unwrap()
+                    .location_tracker
+                    .on_server_change();
             }
             packets::opcodes::Pkt::SyncNearEntities => {
                 // info!("Received {op:?}");
@@ -49,17 +92,18 @@ pub async fn start(app_handle: AppHandle) {
                             continue;
                         }
                     };
+                let encounter_state = app_handle.state::<EncounterMutex>();
+                let mut encounter_state = encounter_state.lock().unwrap();
                 let player_state_mutex = app_handle.state::<PlayerStateMutex>();
                 let player_state = player_state_mutex.lock().unwrap();
                 let player_cache_mutex = app_handle.state::<PlayerCacheMutex>();
-                let encounter_state = app_handle.state::<EncounterMutex>();
-                let mut encounter_state = encounter_state.lock().unwrap();
                 if process_sync_near_entities(
                     &mut encounter_state,
                     sync_near_entities,
                     &player_state,
                     is_bptimer_enabled(&bptimer_enabled_state),
                     Some(&player_cache_mutex),
+                    &server_clock,
                 )
                 .is_none()
                 {
@@ -80,6 +124,7 @@ pub async fn start(app_handle: AppHandle) {
                     };
 
                 // Store persistent player identity data
+                let mut location = None;
                 if let Some(v_data) = &sync_container_data.v_data {
                     let player_state_mutex = app_handle.state::<PlayerStateMutex>();
                     let mut player_state = player_state_mutex.lock().unwrap();
@@ -93,11 +138,12 @@ pub async fn start(app_handle: AppHandle) {
                         }
                     }
 
-                    // Extract and store line_id
+                    // Track map, line and dungeon
                     if let Some(scene_data) = &v_data.scene_data {
-                        if let Some(line_id) = scene_data.line_id {
-                            player_state.set_line_id(line_id);
-                        }
+                        player_state
+                            .location_tracker
+                            .process_scene_data(scene_data, server_clock.now_ms());
+                        location.clone_from(&player_state.location_tracker.location);
                     }
                 }
 
@@ -105,6 +151,10 @@ pub async fn start(app_handle: AppHandle) {
                 let encounter_state = app_handle.state::<EncounterMutex>();
                 let mut encounter_state = encounter_state.lock().unwrap();
                 encounter_state.local_player = Some(sync_container_data.clone());
+                encounter_state.local_player_ms = Some(server_clock.now_ms());
+                if location.is_some() {
+                    encounter_state.location = location;
+                }
                 if process_sync_container_data(
                     &mut encounter_state,
                     sync_container_data,
@@ -132,18 +182,53 @@ pub async fn start(app_handle: AppHandle) {
             //         warn!("Error processing SyncToMeDeltaInfo.. ignoring.");
             //     }
             // }
-            packets::opcodes::Pkt::SyncServerTime => {
-                // info!("Received {op:?}");
+            packets::opcodes::Pkt::SyncDungeonData => {
                 // trace!("Received {op:?} and data {data:?}");
-                let _sync_server_time =
-                    match blueprotobuf::SyncServerTime::decode(Bytes::from(data)) {
+                let sync_dungeon_data =
+                    match blueprotobuf::SyncDungeonData::decode(Bytes::from(data)) {
                         Ok(v) => v,
                         Err(e) => {
-                            warn!("Error decoding SyncServerTime.. ignoring: {e}");
+                            warn!("Error decoding SyncDungeonData.. ignoring: {e}");
                             continue;
                         }
                     };
-                // todo: this is skipped, not sure what info it has
+                let Some(dungeon_sync_data) = sync_dungeon_data.v_data else {
+                    continue;
+                };
+                // Released before locking the encounter manager, see the lock order in lib.rs
+                let location = app_handle
+                    .state::<PlayerStateMutex>()
+                    .lock()
+                    .unwrap()
+                    .location_tracker
+                    .location
+                    .clone();
+                if encounter_manager_state
+                    .lock()
+                    .unwrap()
+                    .dungeon_tracker
+                    .process_dungeon_sync_data(
+                        dungeon_sync_data,
+                        location.as_ref(),
+                        server_clock.now_ms(),
+                    )
+                    .is_none()
+                {
+                    warn!("Error processing SyncDungeonData.. ignoring.");
+                }
+            }
+            packets::opcodes::Pkt::SyncServerTime => {
+                // info!("Received {op:?}");
+                // trace!("Received {op:?} and data {data:?}");
+                let sync_server_time = match blueprotobuf::SyncServerTime::decode(Bytes::from(data))
+                {
+                    Ok(v) => v,
+                    Err(e) => {
+                        warn!("Error decoding SyncServerTime.. ignoring: {e}");
+                        continue;
+                    }
+                };
+                server_clock.on_sync_server_time(&sync_server_time);
             }
             packets::opcodes::Pkt::SyncToMeDeltaInfo => {
                 // todo: fix this, attrs dont include name, no idea why
@@ -172,17 +257,22 @@ pub async fn start(app_handle: AppHandle) {
                     }
                 }
 
+                let encounter_state = app_handle.state::<EncounterMutex>();
+                let mut encounter_state = encounter_state.lock().unwrap();
+                encounter_manager_state
+                    .lock()
+                    .unwrap()
+                    .close_if_needed(&mut encounter_state, server_clock.now_ms());
                 let player_state_mutex = app_handle.state::<PlayerStateMutex>();
                 let player_state = player_state_mutex.lock().unwrap();
                 let player_cache_mutex = app_handle.state::<PlayerCacheMutex>();
-                let encounter_state = app_handle.state::<EncounterMutex>();
-                let mut encounter_state = encounter_state.lock().unwrap();
                 if process_sync_to_me_delta_info(
                     &mut encounter_state,
                     sync_to_me_delta_info,
                     &player_state,
                     is_bptimer_enabled(&bptimer_enabled_state),
                     Some(&player_cache_mutex),
+                    &server_clock,
                 )
                 .is_none()
                 {
@@ -200,11 +290,15 @@ pub async fn start(app_handle: AppHandle) {
                             continue;
                         }
                     };
+                let encounter_state = app_handle.state::<EncounterMutex>();
+                let mut encounter_state = encounter_state.lock().unwrap();
+                encounter_manager_state
+                    .lock()
+                    .unwrap()
+                    .close_if_needed(&mut encounter_state, server_clock.now_ms());
                 let player_state_mutex = app_handle.state::<PlayerStateMutex>();
                 let player_state = player_state_mutex.lock().unwrap();
                 let player_cache_mutex = app_handle.state::<PlayerCacheMutex>();
-                let encounter_state = app_handle.state::<EncounterMutex>();
-                let mut encounter_state = encounter_state.lock().unwrap();
                 for aoi_sync_delta in sync_near_delta_info.delta_infos {
                     if process_aoi_sync_delta(
                         &mut encounter_state,
@@ -212,6 +306,7 @@ pub async fn start(app_handle: AppHandle) {
                         &player_state,
                         is_bptimer_enabled(&bptimer_enabled_state),
                         Some(&player_cache_mutex),
+                        &server_clock,
                     )
                     .is_none()
                     {
@@ -0,0 +1,125 @@
+use blueprotobuf_lib::blueprotobuf;
+use log::info;
+
+/// Where the local player is, as told by the `SceneData` of the last `SyncContainerData`
+#[derive(
+    specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone, PartialEq, Eq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub map_id: Option<u32>,
+    pub channel_id: Option<u32>,
+    pub level_map_id: Option<u32>, // dungeon/instance map, the open world map otherwise
+    pub scene_guid: Option<String>,
+    pub dungeon_guid: Option<String>, // unique per dungeon instance, None outside of dungeons
+    pub plane_id: Option<u32>,
+    pub line_id: Option<u32>,
+}
+
+impl Location {
+    pub fn from_scene_data(scene_data: &blueprotobuf::SceneData) -> Self {
+        let non_empty = |guid: &Option<String>| guid.clone().filter(|guid| !guid.is_empty());
+        Self {
+            map_id: scene_data.map_id,
+            channel_id: scene_data.channel_id,
+            level_map_id: scene_data.level_map_id,
+            scene_guid: non_empty(&scene_data.scene_guid),
+            dungeon_guid: non_empty(&scene_data.dungeon_guid),
+            plane_id: scene_data.plane_id,
+            line_id: scene_data.line_id,
+        }
+    }
+
+    pub fn is_dungeon(&self) -> bool {
+        self.dungeon_guid.is_some()
+    }
+}
+
+/// Current location of the local player, survives encounter resets
+#[derive(Debug, Default, Clone)]
+pub struct LocationTracker {
+    pub location: Option<Location>,
+    pub entered_ms: Option<u128>, // when the current scene was first seen
+}
+
+impl LocationTracker {
+    pub fn process_scene_data(&mut self, scene_data: &blueprotobuf::SceneData, timestamp_ms: u128) {
+        let location = Location::from_scene_data(scene_data);
+        let is_same_scene = self.location.as_ref().is_some_and(|current_location| {
+            current_location.map_id == location.map_id
+                && current_location.level_map_id == location.level_map_id
+                && current_location.scene_guid == location.scene_guid
+        });
+        if !is_same_scene {
+            info!(
+                "entered map {:?} (level map {:?}, line {:?}, dungeon {:?})",
+                location.map_id, location.level_map_id, location.line_id, location.dungeon_guid
+            );
+            self.entered_ms = Some(timestamp_ms);
+        }
+        self.location = Some(location);
+    }
+
+    /// The next `SyncContainerData` tells where we landed, until then the location is unknown
+    pub fn on_server_change(&mut self) {
+        self.location = None;
+        self.entered_ms = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::location_tracker::{Location, LocationTracker};
+    use blueprotobuf_lib::blueprotobuf::SceneData;
+
+    fn scene_data(scene_guid: &str, line_id: u32) -> SceneData {
+        SceneData {
+            map_id: Some(3),
+            level_map_id: Some(301),
+            scene_guid: Some(scene_guid.to_string()),
+            dungeon_guid: Some(String::new()),
+            line_id: Some(line_id),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_from_scene_data() {
+        let location = Location::from_scene_data(&scene_data("scene-1", 12));
+        assert_eq!(location.scene_guid.as_deref(), Some("scene-1"));
+        // Empty guids are sent outside of dungeons
+        assert_eq!(location.dungeon_guid, None);
+        assert!(!location.is_dungeon());
+    }
+
+    #[test]
+    fn test_same_scene_keeps_entered_ms() {
+        let mut location_tracker = LocationTracker::default();
+        location_tracker.process_scene_data(&scene_data("scene-1", 12), 1_000);
+        assert_eq!(location_tracker.entered_ms, Some(1_000));
+
+        // Resent on every SyncContainerData, a line switch stays in the same scene
+        location_tracker.process_scene_data(&scene_data("scene-1", 14), 5_000);
+        assert_eq!(location_tracker.entered_ms, Some(1_000));
+        assert_eq!(
+            location_tracker.location.as_ref().unwrap().line_id,
+            Some(14)
+        );
+
+        location_tracker.process_scene_data(&scene_data("scene-2", 14), 9_000);
+        assert_eq!(location_tracker.entered_ms, Some(9_000));
+    }
+
+    #[test]
+    fn test_server_change_forgets_location() {
+        let mut location_tracker = LocationTracker::default();
+        location_tracker.process_scene_data(&scene_data("scene-1", 12), 1_000);
+        location_tracker.on_server_change();
+        assert_eq!(location_tracker.location, None);
+        assert_eq!(location_tracker.entered_ms, None);
+
+        // The same scene afterwards is entered anew
+        location_tracker.process_scene_data(&scene_data("scene-1", 12), 5_000);
+        assert_eq!(location_tracker.entered_ms, Some(5_000));
+    }
+}
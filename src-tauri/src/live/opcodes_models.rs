@@ -1,6 +1,18 @@
+use crate::live::body_part_tracker::BodyPartTracker;
+use crate::live::boss_tracker::BossTracker;
+use crate::live::buff_tracker::BuffTracker;
+use crate::live::class_spec_table::CLASS_SPEC_MIN_HITS;
+use crate::live::cooldown_tracker::CooldownTracker;
+use crate::live::damage_flags::DamageFlags;
+use crate::live::death_tracker::DeathTracker;
+use crate::live::event_log::EventLog;
+use crate::live::hate_tracker::HateTracker;
+use crate::live::location_tracker::Location;
 use crate::live::opcodes_models::class::{Class, ClassSpec};
+use crate::live::passive_tracker::PassiveTracker;
+use crate::live::timeline_tracker::TimelineTracker;
 use blueprotobuf_lib::blueprotobuf;
-use blueprotobuf_lib::blueprotobuf::{EEntityType, SyncContainerData};
+use blueprotobuf_lib::blueprotobuf::{EDamageMode, EDisappearType, EEntityType, SyncContainerData};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -10,13 +22,32 @@ pub type EncounterMutex = Arc<Mutex<Encounter>>;
 #[derive(Debug, Default, Clone)]
 pub struct Encounter {
     pub is_encounter_paused: bool,
+    pub now_ms: u128, // server clock as of the last packet or close check, 0 for saved encounters
     pub time_last_combat_packet_ms: u128,
     pub time_fight_start_ms: u128,
     pub entity_uid_to_entity: HashMap<i64, Entity>,
     pub dmg_stats: CombatStats,
     pub dmg_stats_boss_only: CombatStats,
     pub heal_stats: CombatStats,
+    pub buff_tracker: BuffTracker,
+    pub death_tracker: DeathTracker,
+    pub boss_tracker: BossTracker,
+    pub timeline_tracker: TimelineTracker,
+    pub cooldown_tracker: CooldownTracker,
+    pub hate_tracker: HateTracker,
+    pub passive_tracker: PassiveTracker,
+    pub body_part_tracker: BodyPartTracker,
+    pub event_log: EventLog,
     pub local_player: Option<SyncContainerData>,
+    pub local_player_ms: Option<u128>, // when local_player arrived
+    pub location: Option<Location>,    // where the encounter took place
+}
+
+impl Encounter {
+    /// Where rolling windows end: keeps moving while nobody is fighting, saved encounters end at their last combat packet
+    pub fn get_now_ms(&self) -> u128 {
+        self.now_ms.max(self.time_last_combat_packet_ms)
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -32,10 +63,18 @@ pub struct Entity {
     pub heal_stats: CombatStats,
     pub skill_uid_to_heal_stats: HashMap<i32, CombatStats>,
 
+    // The stats above split by direct attacker uid: the entity itself or one of its summons (pets, turrets, ...)
+    pub skill_uid_to_source_dps_stats: HashMap<i32, HashMap<i64, CombatStats>>,
+    pub skill_uid_to_source_dps_stats_boss_only: HashMap<i32, HashMap<i64, CombatStats>>,
+    pub skill_uid_to_source_heal_stats: HashMap<i32, HashMap<i64, CombatStats>>,
+
+    pub taken_dmg_stats: CombatStats,
+
     // Players
     pub name: Option<String>, // also available for monsters in packets
     pub class: Option<Class>,
     pub class_spec: Option<ClassSpec>,
+    pub class_spec_hits: HashMap<ClassSpec, u32>, // hits from spec-exclusive skills
     pub ability_score: Option<i32>,
 
     // Monsters
@@ -43,9 +82,41 @@ pub struct Entity {
     pub curr_hp: Option<i32>, // also available for players in packets
     pub max_hp: Option<i32>,  // also available for players in packets
     pub monster_pos: blueprotobuf::Vector3,
+
+    // Set when the entity leaves (SyncNearEntities.disappear), cleared if it appears again
+    pub departed_ms: Option<u128>,
+    pub disappear_type: Option<EDisappearType>,
 }
 
-#[derive(Debug, Default, Clone)]
+impl Entity {
+    /// The entity never dealt, healed or took damage, so nothing in the meter depends on it
+    pub fn is_stat_less(&self) -> bool {
+        self.dmg_stats.hits == 0 && self.heal_stats.hits == 0 && self.taken_dmg_stats.hits == 0
+    }
+
+    /// Counts a hit from a skill exclusive to `class_spec` (`Unknown` for shared skills), returns the
+    /// spec to switch to once enough hits agree on one and it outnumbers the current spec.
+    /// Only the spec that was just hit can overtake, so on a tie the spec that got there first wins.
+    pub fn record_class_spec_hit(&mut self, class_spec: ClassSpec) -> Option<ClassSpec> {
+        if class_spec == ClassSpec::Unknown {
+            return None;
+        }
+        let hits = self.class_spec_hits.entry(class_spec).or_default();
+        *hits += 1;
+        let hits = *hits;
+        let curr_hits = self
+            .class_spec
+            .and_then(|curr_class_spec| self.class_spec_hits.get(&curr_class_spec))
+            .copied()
+            .unwrap_or_default();
+        (hits >= CLASS_SPEC_MIN_HITS && hits > curr_hits).then_some(class_spec)
+    }
+}
+
+// Gaps between two hits longer than this don't count towards active time
+pub const ACTIVE_TIME_GAP_THRESHOLD_MS: u128 = 5_000;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct CombatStats {
     pub value: i64,
     pub hits: i64,
@@ -53,6 +124,23 @@ pub struct CombatStats {
     pub crit_hits: i64,
     pub lucky_value: i64,
     pub lucky_hits: i64,
+    #[serde(default)]
+    pub first_hit_ms: u128, // 0 = no hit yet
+    #[serde(default)]
+    pub last_hit_ms: u128,
+    #[serde(default)]
+    pub active_time_ms: u128,
+    // Hits per DamageFlags flag
+    #[serde(default)]
+    pub rainbow_hits: i64,
+    #[serde(default)]
+    pub unknown_type_flag_hits: i64, // hits with type_flag bits that aren't understood yet
+    #[serde(default)]
+    pub normal_hits: i64,
+    #[serde(default)]
+    pub physical_hits: i64,
+    #[serde(default)]
+    pub magical_hits: i64,
 }
 
 static SKILL_NAMES: Lazy<HashMap<i32, String>> = Lazy::new(|| {
@@ -61,6 +149,53 @@ static SKILL_NAMES: Lazy<HashMap<i32, String>> = Lazy::new(|| {
 });
 
 impl CombatStats {
+    pub fn add_hit(&mut self, value: i64, damage_flags: &DamageFlags, timestamp_ms: u128) {
+        if damage_flags.is_crit {
+            self.crit_hits += 1;
+            self.crit_value += value;
+        }
+        if damage_flags.is_lucky {
+            self.lucky_hits += 1;
+            self.lucky_value += value;
+        }
+        self.rainbow_hits += i64::from(damage_flags.is_rainbow);
+        self.unknown_type_flag_hits += i64::from(damage_flags.unknown_type_flag_bits() != 0);
+        self.normal_hits += i64::from(damage_flags.is_normal);
+        match damage_flags.damage_mode {
+            EDamageMode::DamagePhysical => self.physical_hits += 1,
+            EDamageMode::DamageMagical => self.magical_hits += 1,
+            EDamageMode::DamageNormal => {}
+        }
+        self.hits += 1;
+        self.value += value;
+        self.record_hit_time(timestamp_ms);
+    }
+
+    pub fn record_hit_time(&mut self, timestamp_ms: u128) {
+        if self.first_hit_ms == 0 {
+            self.first_hit_ms = timestamp_ms;
+        } else {
+            let gap_ms = timestamp_ms.saturating_sub(self.last_hit_ms);
+            if gap_ms <= ACTIVE_TIME_GAP_THRESHOLD_MS {
+                self.active_time_ms += gap_ms;
+            }
+        }
+        self.last_hit_ms = self.last_hit_ms.max(timestamp_ms);
+    }
+
+    /// Value per second of active time, at least one second is counted so a single hit doesn't spike
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_active_value_per_sec(&self) -> f64 {
+        if self.hits == 0 {
+            return 0.0;
+        }
+        self.value as f64 / (self.active_time_ms.max(1000) as f64 / 1000.0)
+    }
+
+    pub fn is_known_skill(skill_uid: i32) -> bool {
+        SKILL_NAMES.contains_key(&skill_uid)
+    }
+
     pub fn get_skill_name(skill_uid: i32) -> String {
         SKILL_NAMES
             .get(&skill_uid)
@@ -69,10 +204,10 @@ impl CombatStats {
     }
 }
 
-// pub static MONSTER_NAMES: Lazy<HashMap<i32, String>> = Lazy::new(|| { // not currently used
-//     let data = include_str!("../../../src/lib/data/json/MonsterName.json");
-//     serde_json::from_str(data).expect("invalid MonsterName.json")
-// });
+pub static MONSTER_NAMES: Lazy<HashMap<i32, String>> = Lazy::new(|| {
+    let data = include_str!("../../../src/lib/data/json/MonsterName.json");
+    serde_json::from_str(data).expect("invalid MonsterName.json")
+});
 
 pub static MONSTER_NAMES_BOSS: Lazy<HashMap<i32, String>> = Lazy::new(|| {
     let data = include_str!("../../../src/lib/data/json/MonsterNameBoss.json");
@@ -84,6 +219,15 @@ pub static MONSTER_NAMES_CROWDSOURCE: Lazy<HashMap<i32, String>> = Lazy::new(||
     serde_json::from_str(data).expect("invalid MonsterName.json")
 });
 
+/// Boss names take priority, they're curated
+pub fn get_monster_name(monster_id: i32) -> Option<String> {
+    MONSTER_NAMES_BOSS
+        .get(&monster_id)
+        .or_else(|| MONSTER_NAMES.get(&monster_id))
+        .or_else(|| MONSTER_NAMES_CROWDSOURCE.get(&monster_id))
+        .cloned()
+}
+
 pub mod attr_type {
     pub const ATTR_NAME: i32 = 0x01;
     pub const ATTR_ID: i32 = 0x0a;
@@ -105,7 +249,7 @@ pub mod attr_type {
 // TODO: this logic needs to be severely cleaned up
 pub mod class {
 
-    #[derive(Debug, Default, Clone, Copy)]
+    #[derive(Debug, Default, Clone, Copy, serde::Serialize, serde::Deserialize)]
     #[repr(i32)]
     pub enum Class {
         Stormblade,
@@ -152,7 +296,9 @@ pub mod class {
         })
     }
 
-    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    #[derive(
+        Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+    )]
     pub enum ClassSpec {
         // Stormblade
         Iaido,
@@ -182,35 +328,6 @@ pub mod class {
         Unknown,
     }
 
-    pub fn get_class_spec_from_skill_id(skill_id: i32) -> ClassSpec {
-        match skill_id {
-            1714 | 1734 => ClassSpec::Iaido, // Iaido Slash, Thunder Iaido Slash
-            44701 | 179_906 => ClassSpec::Moonstrike, // AI: Moon Blade, Moonstrike Whirl
-
-            120_901 | 120_902 => ClassSpec::Icicle, // AI: Through the ice spear, AI: Ice spear
-            1241 => ClassSpec::Frostbeam,           // Frostbeam
-
-            1405 | 1418 => ClassSpec::Vanguard, // Gale Thrust, Gale Thrust
-            1419 => ClassSpec::Skyward,         // Skyfall
-
-            1518 | 1541 | 21402 => ClassSpec::Smite, // Wild Bloom, Wild Bloom, AI: Blooming wildly
-            20301 => ClassSpec::Lifebind,            // AI: Life blooms
-
-            199_902 => ClassSpec::Earthfort, // Rage Burst Stone
-            1930 | 1931 | 1934 | 1935 => ClassSpec::Block, // Countercrush, Countercrush, Countercrush, Countercrush
-
-            220_112 | 2_203_622 => ClassSpec::Falconry, // AI: Photoelectric cracks, AI: Light lip sputtering
-            2292 | 1_700_820 | 1_700_825 | 1_700_827 => ClassSpec::Wildpack, // Phantom Direwolves, Wild Wolf - Assist, Pet - Foxen Pounce, Pet - Basic Attack
-
-            2405 => ClassSpec::Recovery, // Valor Bash
-            2406 => ClassSpec::Shield,   // Vanguard Strike
-
-            2306 => ClassSpec::Dissonance, // Amplified Beat
-            2307 | 2361 | 55302 => ClassSpec::Concerto, // Healing Beat, Healing Beat copy, AI: Healing beat
-            _ => ClassSpec::Unknown,
-        }
-    }
-
     pub fn get_class_from_spec(class_spec: ClassSpec) -> Class {
         match class_spec {
             ClassSpec::Iaido | ClassSpec::Moonstrike => Class::Stormblade,
@@ -248,3 +365,73 @@ pub mod class {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::live::class_spec_table::{CLASS_SPEC_MIN_HITS, get_class_spec_from_skill_id};
+    use crate::live::opcodes_models::Entity;
+    use crate::live::opcodes_models::class::ClassSpec;
+
+    const SHARED_SKILL_ID: i32 = 1701; // Stormblade basic attack
+    const IAIDO_SKILL_ID: i32 = 1714;
+    const MOONSTRIKE_SKILL_ID: i32 = 44701;
+
+    fn record_hits(entity: &mut Entity, skill_id: i32, hits: u32) -> Option<ClassSpec> {
+        (0..hits)
+            .map(|_| entity.record_class_spec_hit(get_class_spec_from_skill_id(skill_id)))
+            .last()
+            .flatten()
+    }
+
+    #[test]
+    fn test_shared_skill_infers_nothing() {
+        let mut entity = Entity::default();
+        assert_eq!(record_hits(&mut entity, SHARED_SKILL_ID, 10), None);
+        assert!(entity.class_spec_hits.is_empty());
+    }
+
+    #[test]
+    fn test_too_few_hits_infer_nothing() {
+        let mut entity = Entity::default();
+        assert_eq!(
+            record_hits(&mut entity, IAIDO_SKILL_ID, CLASS_SPEC_MIN_HITS - 1),
+            None
+        );
+        assert_eq!(
+            record_hits(&mut entity, IAIDO_SKILL_ID, 1),
+            Some(ClassSpec::Iaido)
+        );
+    }
+
+    #[test]
+    fn test_wrong_cached_spec_overturned() {
+        let mut entity = Entity {
+            class_spec: Some(ClassSpec::Moonstrike),
+            ..Default::default()
+        };
+        record_hits(&mut entity, MOONSTRIKE_SKILL_ID, 1);
+        // Outnumbering the cached spec's own hits is what overturns it
+        assert_eq!(
+            record_hits(&mut entity, IAIDO_SKILL_ID, CLASS_SPEC_MIN_HITS - 1),
+            None
+        );
+        assert_eq!(
+            record_hits(&mut entity, IAIDO_SKILL_ID, 1),
+            Some(ClassSpec::Iaido)
+        );
+    }
+    #[test]
+    fn test_tie_keeps_current_spec() {
+        let mut entity = Entity::default();
+        entity.class_spec = record_hits(&mut entity, MOONSTRIKE_SKILL_ID, CLASS_SPEC_MIN_HITS);
+        assert_eq!(entity.class_spec, Some(ClassSpec::Moonstrike));
+        assert_eq!(
+            record_hits(&mut entity, IAIDO_SKILL_ID, CLASS_SPEC_MIN_HITS),
+            None
+        );
+        assert_eq!(
+            record_hits(&mut entity, IAIDO_SKILL_ID, 1),
+            Some(ClassSpec::Iaido)
+        );
+    }
+}
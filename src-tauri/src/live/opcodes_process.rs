@@ -1,7 +1,11 @@
+use crate::live::boss_tracker::BossTracker;
 use crate::live::bptimer::BPTimerClient;
-use crate::live::opcodes_models::class::{
-    Class, ClassSpec, get_class_from_spec, get_class_spec_from_skill_id,
-};
+use crate::live::class_spec_table::get_class_spec_from_skill_id;
+use crate::live::clock::Clock;
+use crate::live::damage_flags::DamageFlags;
+use crate::live::death_tracker::IncomingEvent;
+use crate::live::event_log::HitEvent;
+use crate::live::opcodes_models::class::{Class, get_class_from_spec};
 use crate::live::opcodes_models::{CombatStats, Encounter, Entity, MONSTER_NAMES_BOSS, attr_type};
 use crate::live::player_state::{PlayerCacheMutex, PlayerState};
 use crate::packets::utils::BinaryReader;
@@ -11,7 +15,9 @@ use log::{debug, info, warn};
 use once_cell::sync::Lazy;
 use prost::Message;
 use std::default::Default;
-use std::time::{SystemTime, UNIX_EPOCH};
+
+// Departed entities without stats are dropped after this long, so long sessions don't keep every mob seen
+const DEPARTED_ENTITY_TTL_MS: u128 = 60_000;
 
 // Needed for Github Actions compile-time env vars
 const COMPILE_TIME_ENDPOINT: Option<&str> = option_env!("BP_TIMER_ENDPOINT");
@@ -52,7 +58,42 @@ pub fn process_sync_near_entities(
     player_state: &PlayerState,
     is_bptimer_enabled: bool,
     player_cache: Option<&PlayerCacheMutex>,
+    clock: &dyn Clock,
 ) -> Option<()> {
+    let timestamp_ms = clock.now_ms();
+    // Handled first since appear bails out on entities without attrs
+    for disappear_entity in sync_near_entities.disappear {
+        let Some(target_uuid) = disappear_entity.uuid else {
+            continue;
+        };
+        let target_uid = target_uuid >> 16;
+        let Some(target_entity) = encounter.entity_uid_to_entity.get_mut(&target_uid) else {
+            continue;
+        };
+        let disappear_type = disappear_entity
+            .r#type
+            .and_then(|disappear_type| blueprotobuf::EDisappearType::try_from(disappear_type).ok())
+            .unwrap_or(blueprotobuf::EDisappearType::EDisappearNormal);
+        debug!("{target_uid} disappeared: {}", disappear_type.as_str_name());
+        target_entity.departed_ms = Some(timestamp_ms);
+        target_entity.disappear_type = Some(disappear_type);
+
+        // The killing blow isn't always seen, don't leave dead monsters looking alive
+        if disappear_type == blueprotobuf::EDisappearType::EDisappearDead
+            && target_entity.entity_type == blueprotobuf::EEntityType::EntMonster
+            && target_entity.curr_hp.is_some_and(|curr_hp| curr_hp > 0)
+        {
+            target_entity.curr_hp = Some(0);
+            record_boss_hp(
+                &mut encounter.boss_tracker,
+                target_entity,
+                target_uid,
+                timestamp_ms,
+            );
+        }
+    }
+    evict_departed_entities(encounter, timestamp_ms);
+
     for pkt_entity in sync_near_entities.appear {
         let target_uuid = pkt_entity.uuid?;
         let target_uid = target_uuid >> 16;
@@ -63,6 +104,8 @@ pub fn process_sync_near_entities(
             .entry(target_uid)
             .or_default();
         target_entity.entity_type = target_entity_type;
+        target_entity.departed_ms = None;
+        target_entity.disappear_type = None;
 
         match target_entity_type {
             blueprotobuf::EEntityType::EntChar => process_player_attrs(
@@ -71,18 +114,45 @@ pub fn process_sync_near_entities(
                 pkt_entity.attrs?.attrs,
                 player_cache,
             ),
-            blueprotobuf::EEntityType::EntMonster => process_monster_attrs(
-                target_entity,
-                pkt_entity.attrs?.attrs,
-                player_state,
-                is_bptimer_enabled,
-            ),
+            blueprotobuf::EEntityType::EntMonster => {
+                process_monster_attrs(
+                    target_entity,
+                    pkt_entity.attrs?.attrs,
+                    player_state,
+                    is_bptimer_enabled,
+                    timestamp_ms,
+                );
+                record_boss_hp(
+                    &mut encounter.boss_tracker,
+                    target_entity,
+                    target_uid,
+                    timestamp_ms,
+                );
+            }
             _ => {}
         }
     }
     Some(())
 }
 
+/// Drops entities that left a while ago and never dealt damage or healing, bosses are kept for the boss window
+fn evict_departed_entities(encounter: &mut Encounter, timestamp_ms: u128) {
+    let len_before = encounter.entity_uid_to_entity.len();
+    encounter.entity_uid_to_entity.retain(|_, entity| {
+        let is_expired = entity.departed_ms.is_some_and(|departed_ms| {
+            timestamp_ms.saturating_sub(departed_ms) > DEPARTED_ENTITY_TTL_MS
+        });
+        let is_boss = entity
+            .monster_id
+            .is_some_and(|monster_id| MONSTER_NAMES_BOSS.contains_key(&monster_id));
+        !is_expired || is_boss || !entity.is_stat_less()
+    });
+    let evicted = len_before - encounter.entity_uid_to_entity.len();
+    if evicted > 0 {
+        debug!("evicted {evicted} departed entities");
+    }
+}
+
 pub fn process_sync_container_data(
     encounter: &mut Encounter,
     sync_container_data: blueprotobuf::SyncContainerData,
@@ -125,14 +195,25 @@ pub fn process_sync_to_me_delta_info(
     player_state: &PlayerState,
     is_bptimer_enabled: bool,
     player_cache: Option<&PlayerCacheMutex>,
+    clock: &dyn Clock,
 ) -> Option<()> {
     let delta_info = sync_to_me_delta_info.delta_info?;
+    encounter
+        .cooldown_tracker
+        .process_skill_cds(&delta_info.sync_skill_c_ds);
+    encounter
+        .cooldown_tracker
+        .process_fight_res_cds(&delta_info.fight_res_c_ds);
+    encounter
+        .hate_tracker
+        .process_hate_ids(&delta_info.sync_hate_ids, clock.now_ms());
     process_aoi_sync_delta(
         encounter,
         delta_info.base_delta?,
         player_state,
         is_bptimer_enabled,
         player_cache,
+        clock,
     )
 }
 
@@ -142,12 +223,15 @@ pub fn process_aoi_sync_delta(
     player_state: &PlayerState,
     is_bptimer_enabled: bool,
     player_cache: Option<&PlayerCacheMutex>,
+    clock: &dyn Clock,
 ) -> Option<()> {
     let target_uuid = aoi_sync_delta.uuid?; // UUID =/= uid (have to >> 16)
     let target_uid = target_uuid >> 16;
+    let timestamp_ms = clock.now_ms();
 
     // Process attributes
     let target_entity_type = blueprotobuf::EEntityType::from(target_uuid);
+    let mut has_hit_zero_hp = false;
     {
         let target_entity = encounter
             .entity_uid_to_entity
@@ -156,6 +240,7 @@ pub fn process_aoi_sync_delta(
                 entity_type: target_entity_type,
                 ..Default::default()
             });
+        let prev_hp = target_entity.curr_hp;
 
         if let Some(attrs_collection) = aoi_sync_delta.attrs {
             match target_entity_type {
@@ -165,18 +250,82 @@ pub fn process_aoi_sync_delta(
                     attrs_collection.attrs,
                     player_cache,
                 ),
-                blueprotobuf::EEntityType::EntMonster => process_monster_attrs(
-                    target_entity,
-                    attrs_collection.attrs,
-                    player_state,
-                    is_bptimer_enabled,
-                ),
+                blueprotobuf::EEntityType::EntMonster => {
+                    process_monster_attrs(
+                        target_entity,
+                        attrs_collection.attrs,
+                        player_state,
+                        is_bptimer_enabled,
+                        timestamp_ms,
+                    );
+                    record_boss_hp(
+                        &mut encounter.boss_tracker,
+                        target_entity,
+                        target_uid,
+                        timestamp_ms,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        // Deaths from HP are recorded after the hits, the killing blow may be in this same delta
+        if target_entity_type == blueprotobuf::EEntityType::EntChar {
+            match (prev_hp, target_entity.curr_hp) {
+                (Some(prev_hp), Some(0)) if prev_hp > 0 => has_hit_zero_hp = true,
+                (_, Some(curr_hp)) if curr_hp > 0 => encounter.death_tracker.on_revive(target_uid),
                 _ => {}
             }
         }
     }
 
+    // Process buffs
+    if let Some(buff_infos) = aoi_sync_delta.buff_infos {
+        encounter.buff_tracker.process_buff_infos(
+            buff_infos,
+            target_uuid,
+            timestamp_ms,
+            &mut encounter.event_log,
+        );
+    }
+    if let Some(buff_effect) = aoi_sync_delta.buff_effect {
+        encounter.buff_tracker.process_buff_effects(
+            buff_effect,
+            target_uuid,
+            timestamp_ms,
+            &mut encounter.event_log,
+        );
+    }
+
+    // Process passives
+    if let Some(passive_skill_infos) = aoi_sync_delta.passive_skill_infos {
+        encounter.passive_tracker.process_passive_infos(
+            passive_skill_infos,
+            target_uuid,
+            timestamp_ms,
+        );
+    }
+    if let Some(passive_skill_end_infos) = aoi_sync_delta.passive_skill_end_infos {
+        encounter.passive_tracker.process_passive_end_infos(
+            passive_skill_end_infos,
+            target_uuid,
+            timestamp_ms,
+        );
+    }
+
+    // Process body parts
+    if let Some(body_part_infos) = aoi_sync_delta.body_part_infos {
+        encounter.body_part_tracker.process_body_part_infos(
+            body_part_infos,
+            target_uuid,
+            timestamp_ms,
+        );
+    }
+
     let Some(skill_effect) = aoi_sync_delta.skill_effects else {
+        if has_hit_zero_hp {
+            record_hp_death(encounter, target_uid, timestamp_ms);
+        }
         return Some(()); // return ok since this variable usually doesn't exist
     };
 
@@ -195,6 +344,9 @@ pub fn process_aoi_sync_delta(
             continue; // Skip this damage packet if no attacker
         };
         let attacker_uid = attacker_uuid >> 16;
+        // Summon hits are credited to their owner, the direct attacker tells them apart
+        let direct_attacker_uuid = sync_damage_info.attacker_uuid.unwrap_or(attacker_uuid);
+        let direct_attacker_uid = direct_attacker_uuid >> 16;
         let attacker_entity = encounter
             .entity_uid_to_entity
             .entry(attacker_uid)
@@ -206,11 +358,10 @@ pub fn process_aoi_sync_delta(
         let Some(skill_uid) = sync_damage_info.owner_id else {
             continue; // Skip this damage packet if no skill_uid
         };
-        if attacker_entity
-            .class_spec
-            .is_none_or(|class_spec| class_spec == ClassSpec::Unknown)
+        // Shared skills don't tell the spec apart, only infer it from enough spec-exclusive hits
+        if let Some(class_spec) =
+            attacker_entity.record_class_spec_hit(get_class_spec_from_skill_id(skill_uid))
         {
-            let class_spec = get_class_spec_from_skill_id(skill_uid);
             attacker_entity.class_spec = Some(class_spec);
 
             // Only infer/overwrite class if it's not already set or is Unknown/Unimplemented
@@ -242,14 +393,60 @@ pub fn process_aoi_sync_delta(
         // Skills
         let is_heal =
             sync_damage_info.r#type.unwrap_or(0) == blueprotobuf::EDamageType::Heal as i32;
+
+        // Deaths
+        if target_entity_type == blueprotobuf::EEntityType::EntChar {
+            let is_dead = sync_damage_info.is_dead.unwrap_or_default();
+            let mut incoming_stats = CombatStats::default();
+            process_stats(&sync_damage_info, &mut incoming_stats, timestamp_ms);
+            encounter.death_tracker.record_incoming(
+                target_uid,
+                IncomingEvent {
+                    timestamp_ms,
+                    source_uid: attacker_uid,
+                    skill_uid,
+                    value: incoming_stats.value,
+                    is_heal,
+                    is_crit: incoming_stats.crit_hits > 0,
+                    is_lucky: incoming_stats.lucky_hits > 0,
+                    is_dead,
+                },
+            );
+            if is_dead {
+                if let Some(death) = encounter.death_tracker.on_death(
+                    target_uid,
+                    timestamp_ms,
+                    Some((attacker_uid, skill_uid)),
+                ) {
+                    encounter.event_log.record_death(death);
+                }
+            }
+        }
         if is_heal {
             let heal_skill = attacker_entity
                 .skill_uid_to_heal_stats
                 .entry(skill_uid)
                 .or_default();
-            process_stats(&sync_damage_info, heal_skill);
-            process_stats(&sync_damage_info, &mut attacker_entity.heal_stats); // update total entity heal stats
-            process_stats(&sync_damage_info, &mut encounter.heal_stats); // update total encounter heal stats
+            process_stats(&sync_damage_info, heal_skill, timestamp_ms);
+            let source_heal_skill = attacker_entity
+                .skill_uid_to_source_heal_stats
+                .entry(skill_uid)
+                .or_default()
+                .entry(direct_attacker_uid)
+                .or_default();
+            process_stats(&sync_damage_info, source_heal_skill, timestamp_ms);
+            // update total entity heal stats
+            let value = process_stats(
+                &sync_damage_info,
+                &mut attacker_entity.heal_stats,
+                timestamp_ms,
+            );
+            if attacker_entity.entity_type == blueprotobuf::EEntityType::EntChar {
+                encounter
+                    .timeline_tracker
+                    .record_heal(attacker_uid, value, timestamp_ms);
+            }
+            process_stats(&sync_damage_info, &mut encounter.heal_stats, timestamp_ms); // update total encounter heal stats
             debug!(
                 "dmg packet: {attacker_uid} to {target_uid}: {} total heal",
                 heal_skill.value
@@ -259,30 +456,137 @@ pub fn process_aoi_sync_delta(
                 .skill_uid_to_dps_stats
                 .entry(skill_uid)
                 .or_default();
-            process_stats(&sync_damage_info, dps_skill);
-            process_stats(&sync_damage_info, &mut attacker_entity.dmg_stats); // update total entity dmg stats
-            process_stats(&sync_damage_info, &mut encounter.dmg_stats); // update total encounter heal stats
+            process_stats(&sync_damage_info, dps_skill, timestamp_ms);
+            let source_dps_skill = attacker_entity
+                .skill_uid_to_source_dps_stats
+                .entry(skill_uid)
+                .or_default()
+                .entry(direct_attacker_uid)
+                .or_default();
+            process_stats(&sync_damage_info, source_dps_skill, timestamp_ms);
+            // update total entity dmg stats
+            let value = process_stats(
+                &sync_damage_info,
+                &mut attacker_entity.dmg_stats,
+                timestamp_ms,
+            );
+            if attacker_entity.entity_type == blueprotobuf::EEntityType::EntChar {
+                encounter
+                    .timeline_tracker
+                    .record_dmg(attacker_uid, value, is_boss, timestamp_ms);
+            }
+            process_stats(&sync_damage_info, &mut encounter.dmg_stats, timestamp_ms); // update total encounter heal stats
             if is_boss {
                 let skill_boss_only = attacker_entity
                     .skill_uid_to_dps_stats_boss_only
                     .entry(skill_uid)
                     .or_default();
-                process_stats(&sync_damage_info, skill_boss_only);
-                process_stats(&sync_damage_info, &mut attacker_entity.dmg_stats_boss_only); // update total entity boss only dmg stats
-                process_stats(&sync_damage_info, &mut encounter.dmg_stats_boss_only); // update total encounter heal stats
+                process_stats(&sync_damage_info, skill_boss_only, timestamp_ms);
+                let source_skill_boss_only = attacker_entity
+                    .skill_uid_to_source_dps_stats_boss_only
+                    .entry(skill_uid)
+                    .or_default()
+                    .entry(direct_attacker_uid)
+                    .or_default();
+                process_stats(&sync_damage_info, source_skill_boss_only, timestamp_ms);
+                // update total entity boss only dmg stats
+                process_stats(
+                    &sync_damage_info,
+                    &mut attacker_entity.dmg_stats_boss_only,
+                    timestamp_ms,
+                );
+                // update total encounter heal stats
+                process_stats(
+                    &sync_damage_info,
+                    &mut encounter.dmg_stats_boss_only,
+                    timestamp_ms,
+                );
+                encounter
+                    .boss_tracker
+                    .record_dmg(target_uid, value, timestamp_ms);
             }
             debug!(
                 "dmg packet: {attacker_uid} to {target_uid}: {} total dmg",
                 dps_skill.value
             );
         }
+
+        // Passives
+        if let Some(passive_stats) = sync_damage_info.passive_uuid.and_then(|passive_uuid| {
+            encounter.passive_tracker.get_passive_stats_mut(
+                direct_attacker_uid,
+                passive_uuid,
+                attacker_uid,
+            )
+        }) {
+            if is_heal {
+                process_stats(
+                    &sync_damage_info,
+                    &mut passive_stats.heal_stats,
+                    timestamp_ms,
+                );
+            } else {
+                process_stats(
+                    &sync_damage_info,
+                    &mut passive_stats.dmg_stats,
+                    timestamp_ms,
+                );
+                if is_boss {
+                    process_stats(
+                        &sync_damage_info,
+                        &mut passive_stats.dmg_stats_boss_only,
+                        timestamp_ms,
+                    );
+                }
+            }
+        }
+
+        // Event log
+        let damage_flags = DamageFlags::decode(&sync_damage_info);
+        encounter.event_log.record_hit(HitEvent {
+            timestamp_ms,
+            attacker_uid,
+            direct_attacker_uid,
+            target_uid,
+            skill_uid,
+            value: get_hit_value(&sync_damage_info),
+            is_heal,
+            is_boss,
+            is_dead: sync_damage_info.is_dead.unwrap_or_default(),
+            type_flag: damage_flags.type_flag,
+            is_lucky: damage_flags.is_lucky,
+            is_rainbow: damage_flags.is_rainbow,
+            is_normal: damage_flags.is_normal,
+            damage_mode: damage_flags.damage_mode as i32,
+            hit_event_id: damage_flags.hit_event_id,
+            passive_uuid: sync_damage_info.passive_uuid,
+        });
+
+        // Damage taken
+        if !is_heal {
+            if let Some(target_entity) = encounter.entity_uid_to_entity.get_mut(&target_uid) {
+                process_stats(
+                    &sync_damage_info,
+                    &mut target_entity.taken_dmg_stats,
+                    timestamp_ms,
+                );
+            }
+        }
+
+        // Body parts
+        if !is_heal {
+            encounter.body_part_tracker.record_part_hits(
+                &sync_damage_info.part_infos,
+                target_uid,
+                attacker_uid,
+            );
+        }
+    }
+    if has_hit_zero_hp {
+        record_hp_death(encounter, target_uid, timestamp_ms);
     }
 
     // Figure out timestamps
-    let timestamp_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_millis();
     if encounter.time_fight_start_ms == Default::default() {
         encounter.time_fight_start_ms = timestamp_ms;
     }
@@ -290,27 +594,34 @@ pub fn process_aoi_sync_delta(
     Some(())
 }
 
-fn process_stats(sync_damage_info: &blueprotobuf::SyncDamageInfo, stats: &mut CombatStats) {
-    // TODO: from testing, first bit is set when there's crit, 3rd bit for if it causes lucky (no idea what that means), require more testing here
-    const CRIT_BIT: i32 = 0b00_00_00_01; // 1st bit
+/// Players hitting 0 HP count as a death even if the killing blow wasn't seen, a no-op if a killing blow already did
+fn record_hp_death(encounter: &mut Encounter, player_uid: i64, timestamp_ms: u128) {
+    if let Some(death) = encounter
+        .death_tracker
+        .on_death(player_uid, timestamp_ms, None)
+    {
+        encounter.event_log.record_death(death);
+    }
+}
 
+fn get_hit_value(sync_damage_info: &blueprotobuf::SyncDamageInfo) -> i64 {
     let non_lucky_dmg = sync_damage_info.value;
     let lucky_value = sync_damage_info.lucky_value;
-    let actual_value = non_lucky_dmg.or(lucky_value).unwrap_or(0); // The damage is either non-lucky or lucky (exclusive)
-
-    let is_lucky = lucky_value.is_some();
-    let flag = sync_damage_info.type_flag.unwrap_or_default();
-    let is_crit = (flag & CRIT_BIT) != 0; // No idea why, but SyncDamageInfo.is_crit isn't correct
-    if is_crit {
-        stats.crit_hits += 1;
-        stats.crit_value += actual_value;
-    }
-    if is_lucky {
-        stats.lucky_hits += 1;
-        stats.lucky_value += actual_value;
-    }
-    stats.hits += 1;
-    stats.value += actual_value;
+    non_lucky_dmg.or(lucky_value).unwrap_or(0) // The damage is either non-lucky or lucky (exclusive)
+}
+
+fn process_stats(
+    sync_damage_info: &blueprotobuf::SyncDamageInfo,
+    stats: &mut CombatStats,
+    timestamp_ms: u128,
+) -> i64 {
+    let actual_value = get_hit_value(sync_damage_info);
+    stats.add_hit(
+        actual_value,
+        &DamageFlags::decode(sync_damage_info),
+        timestamp_ms,
+    );
+    actual_value
 }
 
 fn process_player_attrs(
@@ -380,6 +691,16 @@ fn process_player_attrs(
                 player_entity.ability_score =
                     Some(prost::encoding::decode_varint(&mut raw_bytes.as_slice()).unwrap() as i32)
             }
+            #[allow(clippy::cast_possible_truncation)]
+            attr_type::ATTR_HP => {
+                player_entity.curr_hp =
+                    Some(prost::encoding::decode_varint(&mut raw_bytes.as_slice()).unwrap() as i32)
+            }
+            #[allow(clippy::cast_possible_truncation)]
+            attr_type::ATTR_MAX_HP => {
+                player_entity.max_hp =
+                    Some(prost::encoding::decode_varint(&mut raw_bytes.as_slice()).unwrap() as i32)
+            }
             _ => (),
         }
     }
@@ -390,6 +711,7 @@ fn process_monster_attrs(
     attrs: Vec<blueprotobuf::Attr>,
     player_state: &PlayerState,
     is_bptimer_enabled: bool,
+    timestamp_ms: u128,
 ) {
     // Track if HP was updated during this attribute batch
     // Prevents unnecessary report_hp calls even if catched in the api client itself
@@ -448,6 +770,195 @@ fn process_monster_attrs(
             monster_entity.monster_pos.z,
             account_id,
             uid,
+            timestamp_ms,
         );
     }
 }
+
+fn record_boss_hp(
+    boss_tracker: &mut BossTracker,
+    monster_entity: &Entity,
+    monster_uid: i64,
+    timestamp_ms: u128,
+) {
+    let is_boss = monster_entity
+        .monster_id
+        .is_some_and(|monster_id| MONSTER_NAMES_BOSS.contains_key(&monster_id));
+    if let (true, Some(curr_hp), Some(max_hp)) =
+        (is_boss, monster_entity.curr_hp, monster_entity.max_hp)
+    {
+        boss_tracker.record_hp(monster_uid, curr_hp, max_hp, timestamp_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::saved_encounter::SavedEncounter;
+    use crate::live::clock::PacketTimestampClock;
+    use crate::live::opcodes_models::Encounter;
+    use crate::live::opcodes_models::attr_type;
+    use crate::live::opcodes_process::process_aoi_sync_delta;
+    use crate::live::player_state::PlayerState;
+    use blueprotobuf_lib::blueprotobuf::{
+        AoiSyncDelta, Attr, AttrCollection, EDamageType, SkillEffect, SyncDamageInfo,
+    };
+    use prost::Message;
+
+    const PLAYER_UUID: i64 = (1234 << 16) | 640;
+    const MONSTER_UUID: i64 = (5678 << 16) | 64;
+    const ADD_UUID: i64 = (5679 << 16) | 64;
+
+    /// (capture timestamp, encoded `AoiSyncDelta`) pairs, the way a recording holds them
+    fn recorded_packets() -> Vec<(u128, Vec<u8>)> {
+        (0..20)
+            .map(|i: i32| {
+                let aoi_sync_delta = AoiSyncDelta {
+                    uuid: Some(MONSTER_UUID),
+                    skill_effects: Some(SkillEffect {
+                        damages: vec![SyncDamageInfo {
+                            attacker_uuid: Some(PLAYER_UUID),
+                            owner_id: Some(1_000 + i % 3),
+                            value: Some(1_000 + i64::from(i) * 10),
+                            type_flag: Some(i % 2),
+                            r#type: Some(EDamageType::Normal as i32),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                };
+                (
+                    10_000 + u128::from(i.unsigned_abs()) * 250,
+                    aoi_sync_delta.encode_to_vec(),
+                )
+            })
+            .collect()
+    }
+
+    fn replay(packets: &[(u128, Vec<u8>)]) -> String {
+        serde_json::to_string(&SavedEncounter::from_encounter(
+            &replay_encounter(packets),
+            "reset",
+        ))
+        .unwrap()
+    }
+
+    fn replay_encounter(packets: &[(u128, Vec<u8>)]) -> Encounter {
+        let clock = PacketTimestampClock::default();
+        let player_state = PlayerState::default();
+        let mut encounter = Encounter::default();
+        for (timestamp_ms, data) in packets {
+            clock.set_timestamp_ms(*timestamp_ms);
+            let aoi_sync_delta = AoiSyncDelta::decode(data.as_slice()).unwrap();
+            process_aoi_sync_delta(
+                &mut encounter,
+                aoi_sync_delta,
+                &player_state,
+                false,
+                None,
+                &clock,
+            )
+            .unwrap();
+        }
+        encounter
+    }
+
+    #[test]
+    fn test_replay_is_deterministic() {
+        let packets = recorded_packets();
+        let data = replay(&packets);
+        // Byte for byte, the history db stores this string
+        assert_eq!(data, replay(&packets));
+        let saved_encounter: serde_json::Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(saved_encounter["time_fight_start_ms"], 10_000);
+        assert_eq!(saved_encounter["time_last_combat_packet_ms"], 14_750);
+        assert_eq!(saved_encounter["dmg_stats"]["hits"], 20);
+        assert_eq!(saved_encounter["dmg_stats"]["crit_hits"], 10);
+    }
+
+    #[test]
+    fn test_event_log_replays_live_stats() {
+        let encounter = replay_encounter(&recorded_packets());
+        let event_log = &encounter.event_log;
+        assert_eq!(event_log.dropped_events, 0);
+        assert_eq!(
+            event_log.get_stats(|hit_event| !hit_event.is_heal),
+            encounter.dmg_stats
+        );
+
+        let player_uid = PLAYER_UUID >> 16;
+        let player = &encounter.entity_uid_to_entity[&player_uid];
+        assert_eq!(
+            event_log.get_stats(|hit_event| hit_event.attacker_uid == player_uid),
+            player.dmg_stats
+        );
+        for (skill_uid, skill_stats) in &player.skill_uid_to_dps_stats {
+            assert_eq!(
+                &event_log.get_stats(|hit_event| hit_event.attacker_uid == player_uid
+                    && hit_event.skill_uid == *skill_uid),
+                skill_stats
+            );
+        }
+    }
+
+    fn player_delta(curr_hp: Option<u64>, hit: Option<(i64, i32, bool)>) -> AoiSyncDelta {
+        AoiSyncDelta {
+            uuid: Some(PLAYER_UUID),
+            attrs: curr_hp.map(|curr_hp| {
+                let mut raw_data = Vec::new();
+                prost::encoding::encode_varint(curr_hp, &mut raw_data);
+                AttrCollection {
+                    attrs: vec![Attr {
+                        id: Some(attr_type::ATTR_HP),
+                        raw_data: Some(raw_data),
+                    }],
+                    ..Default::default()
+                }
+            }),
+            skill_effects: hit.map(|(attacker_uuid, skill_uid, is_dead)| SkillEffect {
+                damages: vec![SyncDamageInfo {
+                    attacker_uuid: Some(attacker_uuid),
+                    owner_id: Some(skill_uid),
+                    value: Some(500),
+                    r#type: Some(EDamageType::Normal as i32),
+                    is_dead: Some(is_dead),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_killing_blow_in_hp_delta() {
+        let clock = PacketTimestampClock::default();
+        let player_state = PlayerState::default();
+        let mut encounter = Encounter::default();
+        let deltas = [
+            player_delta(Some(1_000), Some((ADD_UUID, 2, false))),
+            // HP hits 0 in the same delta as the killing blow
+            player_delta(Some(0), Some((MONSTER_UUID, 1, true))),
+        ];
+        for (i, aoi_sync_delta) in deltas.into_iter().enumerate() {
+            clock.set_timestamp_ms(1_000 * (i as u128 + 1));
+            process_aoi_sync_delta(
+                &mut encounter,
+                aoi_sync_delta,
+                &player_state,
+                false,
+                None,
+                &clock,
+            )
+            .unwrap();
+        }
+
+        let deaths = &encounter.death_tracker.deaths;
+        assert_eq!(deaths.len(), 1);
+        assert_eq!(deaths[0].killer_uid, Some(MONSTER_UUID >> 16));
+        assert_eq!(deaths[0].killer_skill_uid, Some(1));
+        let killing_blow = deaths[0].recap.last().unwrap();
+        assert_eq!(killing_blow.source_uid, MONSTER_UUID >> 16);
+        assert!(killing_blow.is_dead);
+    }
+}
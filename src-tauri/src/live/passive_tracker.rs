@@ -0,0 +1,203 @@
+use crate::live::opcodes_models::CombatStats;
+use blueprotobuf_lib::blueprotobuf;
+use log::debug;
+use std::collections::HashMap;
+
+// Hits of a passive can arrive a bit after its end info
+const PASSIVE_END_GRACE_MS: u128 = 10_000;
+
+#[derive(Debug, Default, Clone)]
+struct PassiveInstance {
+    skill_id: i32,
+    begin_time: Option<i64>,
+    ended_ms: Option<u128>, // None while the passive runs
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PassiveStats {
+    pub procs: i64,
+    // Hits linked to the passive through SyncDamageInfo.passive_uuid
+    pub dmg_stats: CombatStats,
+    pub dmg_stats_boss_only: CombatStats,
+    pub heal_stats: CombatStats,
+}
+
+/// Counts passive skill procs (imagines, talents, ...) per actor and the hits they caused
+#[derive(Debug, Default, Clone)]
+pub struct PassiveTracker {
+    // (actor uid, passive uuid) => instance, kept for a grace period after it ends
+    instances: HashMap<(i64, u32), PassiveInstance>,
+    pub actor_uid_to_passive_stats: HashMap<i64, HashMap<i32, PassiveStats>>, // skill_id => stats
+}
+
+impl PassiveTracker {
+    pub fn process_passive_infos(
+        &mut self,
+        seq_passive_skill_info: blueprotobuf::SeqPassiveSkillInfo,
+        default_actor_uuid: i64,
+        timestamp_ms: u128,
+    ) {
+        self.evict_ended(timestamp_ms);
+        let actor_uid = seq_passive_skill_info
+            .actor_uuid
+            .unwrap_or(default_actor_uuid)
+            >> 16;
+        for passive_info in seq_passive_skill_info.passive_infos {
+            let (Some(passive_uuid), Some(skill_id)) = (passive_info.uuid, passive_info.skill_id)
+            else {
+                continue;
+            };
+            let instance = self
+                .instances
+                .entry((actor_uid, passive_uuid.cast_unsigned()))
+                .or_default();
+            // Infos are resent while the passive runs, a new begin time (or a reused uuid) is a new proc
+            let is_new_proc = instance.ended_ms.is_some()
+                || instance.skill_id != skill_id
+                || instance.begin_time != passive_info.begin_time;
+            *instance = PassiveInstance {
+                skill_id,
+                begin_time: passive_info.begin_time,
+                ended_ms: None,
+            };
+            if is_new_proc {
+                self.actor_uid_to_passive_stats
+                    .entry(actor_uid)
+                    .or_default()
+                    .entry(skill_id)
+                    .or_default()
+                    .procs += 1;
+                debug!("passive {skill_id} procced for {actor_uid}");
+            }
+        }
+    }
+
+    pub fn process_passive_end_infos(
+        &mut self,
+        seq_passive_skill_end_info: blueprotobuf::SeqPassiveSkillEndInfo,
+        default_actor_uuid: i64,
+        timestamp_ms: u128,
+    ) {
+        self.evict_ended(timestamp_ms);
+        let actor_uid = seq_passive_skill_end_info
+            .actor_uuid
+            .unwrap_or(default_actor_uuid)
+            >> 16;
+        for passive_uuid in seq_passive_skill_end_info.uuids {
+            // The begin info sends the uuid as an int32, only the low 32 bits are the uuid
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let passive_uuid = passive_uuid as u32;
+            if let Some(instance) = self.instances.get_mut(&(actor_uid, passive_uuid)) {
+                instance.ended_ms.get_or_insert(timestamp_ms);
+            }
+        }
+    }
+
+    fn evict_ended(&mut self, timestamp_ms: u128) {
+        self.instances.retain(|_, instance| {
+            instance
+                .ended_ms
+                .is_none_or(|ended_ms| timestamp_ms.saturating_sub(ended_ms) < PASSIVE_END_GRACE_MS)
+        });
+    }
+
+    /// Stats of the passive `passive_uuid`, credited to the actor it procced on like its procs.
+    /// Summon hits can carry a passive of their owner, so `owner_uid` is tried after `actor_uid`
+    pub fn get_passive_stats_mut(
+        &mut self,
+        actor_uid: i64,
+        passive_uuid: u32,
+        owner_uid: i64,
+    ) -> Option<&mut PassiveStats> {
+        let (proc_uid, skill_id) = [actor_uid, owner_uid].into_iter().find_map(|uid| {
+            self.instances
+                .get(&(uid, passive_uuid))
+                .map(|instance| (uid, instance.skill_id))
+        })?;
+        Some(
+            self.actor_uid_to_passive_stats
+                .entry(proc_uid)
+                .or_default()
+                .entry(skill_id)
+                .or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::passive_tracker::{PASSIVE_END_GRACE_MS, PassiveTracker};
+    use blueprotobuf_lib::blueprotobuf;
+
+    const ACTOR_UUID: i64 = 1234 << 16;
+    const SUMMON_UUID: i64 = 5678 << 16;
+    const PASSIVE_UUID: u32 = 0x8000_0001; // >= 2^31, negative as the begin info's int32
+
+    fn begin(passive_tracker: &mut PassiveTracker, timestamp_ms: u128) {
+        passive_tracker.process_passive_infos(
+            blueprotobuf::SeqPassiveSkillInfo {
+                actor_uuid: Some(ACTOR_UUID),
+                passive_infos: vec![blueprotobuf::PassiveSkillInfo {
+                    uuid: Some(PASSIVE_UUID.cast_signed()),
+                    skill_id: Some(42),
+                    begin_time: Some(1),
+                    ..Default::default()
+                }],
+            },
+            0,
+            timestamp_ms,
+        );
+    }
+
+    fn end(passive_tracker: &mut PassiveTracker, timestamp_ms: u128) {
+        passive_tracker.process_passive_end_infos(
+            blueprotobuf::SeqPassiveSkillEndInfo {
+                actor_uuid: Some(ACTOR_UUID),
+                uuids: vec![i64::from(PASSIVE_UUID.cast_signed())],
+            },
+            0,
+            timestamp_ms,
+        );
+    }
+
+    #[test]
+    fn test_procs_and_hits_credited_to_actor() {
+        let mut passive_tracker = PassiveTracker::default();
+        begin(&mut passive_tracker, 0);
+        // A summon hit carrying its owner's passive
+        passive_tracker
+            .get_passive_stats_mut(SUMMON_UUID >> 16, PASSIVE_UUID, ACTOR_UUID >> 16)
+            .expect("linked to the actor's passive")
+            .dmg_stats
+            .hits += 1;
+
+        let passive_stats = &passive_tracker.actor_uid_to_passive_stats[&(ACTOR_UUID >> 16)][&42];
+        assert_eq!(passive_stats.procs, 1);
+        assert_eq!(passive_stats.dmg_stats.hits, 1);
+        assert!(
+            !passive_tracker
+                .actor_uid_to_passive_stats
+                .contains_key(&(SUMMON_UUID >> 16))
+        );
+    }
+
+    #[test]
+    fn test_ended_passive_evicted_after_grace_period() {
+        let mut passive_tracker = PassiveTracker::default();
+        begin(&mut passive_tracker, 0);
+        end(&mut passive_tracker, 1_000);
+        // Late hits still link during the grace period
+        end(&mut passive_tracker, 1_000 + PASSIVE_END_GRACE_MS - 1);
+        assert!(
+            passive_tracker
+                .get_passive_stats_mut(ACTOR_UUID >> 16, PASSIVE_UUID, ACTOR_UUID >> 16)
+                .is_some()
+        );
+        end(&mut passive_tracker, 1_000 + PASSIVE_END_GRACE_MS);
+        assert!(
+            passive_tracker
+                .get_passive_stats_mut(ACTOR_UUID >> 16, PASSIVE_UUID, ACTOR_UUID >> 16)
+                .is_none()
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::live::location_tracker::LocationTracker;
 use crate::live::opcodes_models::class::{Class, ClassSpec};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -8,7 +9,7 @@ use std::sync::{Arc, Mutex};
 pub struct PlayerState {
     pub account_id: Option<String>,
     pub uid: Option<i64>,
-    pub line_id: Option<u32>,
+    pub location_tracker: LocationTracker,
 }
 
 impl PlayerState {
@@ -21,10 +22,6 @@ impl PlayerState {
         }
     }
 
-    pub fn set_line_id(&mut self, line_id: u32) {
-        self.line_id = Some(line_id);
-    }
-
     pub fn get_account_id(&self) -> Option<String> {
         self.account_id.clone()
     }
@@ -34,7 +31,10 @@ impl PlayerState {
     }
 
     pub fn get_line_id(&self) -> Option<u32> {
-        self.line_id
+        self.location_tracker
+            .location
+            .as_ref()
+            .and_then(|location| location.line_id)
     }
 
     // Just for semantic clarity
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// Length of the rolling "current DPS" window, the settings offer 5s/15s/30s
+pub const DEFAULT_ROLLING_WINDOW_SECS: u32 = 15;
+
+pub type RollingWindowSecsState = Arc<AtomicU32>;
+
+pub fn create_rolling_window_secs(initial: u32) -> RollingWindowSecsState {
+    Arc::new(AtomicU32::new(initial.max(1)))
+}
+
+pub fn set_rolling_window_secs(state: &RollingWindowSecsState, window_secs: u32) {
+    state.store(window_secs.max(1), Ordering::Relaxed);
+}
+
+pub fn get_rolling_window_secs(state: &RollingWindowSecsState) -> u32 {
+    state.load(Ordering::Relaxed)
+}
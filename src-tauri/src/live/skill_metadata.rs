@@ -0,0 +1,178 @@
+use crate::live::class_spec_table::get_class_spec_from_skill_id;
+use crate::live::opcodes_models::CombatStats;
+use crate::live::opcodes_models::class::{Class, ClassSpec, get_class_from_spec};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+static SKILL_ICONS: Lazy<HashMap<i32, String>> = Lazy::new(|| {
+    let data = include_str!("../../../src/lib/data/json/SkillIcon.json");
+    serde_json::from_str(data).expect("invalid SkillIcon.json")
+});
+
+#[derive(
+    specta::Type, serde::Serialize, serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum SkillCategory {
+    Basic,   // basic attack chain
+    Special, // class skills and specialization attacks
+    Ultimate,
+    Imagine,
+    Pet,
+    #[default]
+    Other, // no icon to go by (monster skills, procs, ...)
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SkillMetadata {
+    pub name: String,
+    pub icon: Option<String>, // file name in /images/skills, without the extension
+    pub class: Class,
+    pub class_spec: ClassSpec, // Unknown for skills shared by both specs
+    pub category: SkillCategory,
+}
+
+/// Merges what the game tables tell about a skill: name, icon, owning class/spec and category
+pub fn get_skill_metadata(skill_uid: i32) -> SkillMetadata {
+    let name = CombatStats::get_skill_name(skill_uid);
+    let icon = SKILL_ICONS.get(&skill_uid).cloned();
+    let class_spec = get_class_spec_from_skill_id(skill_uid);
+    let class = match class_spec {
+        ClassSpec::Unknown => icon.as_deref().map_or(Class::Unknown, get_class_from_icon),
+        _ => get_class_from_spec(class_spec),
+    };
+    let category = get_category(&name, icon.as_deref());
+    SkillMetadata {
+        name,
+        icon,
+        class,
+        class_spec,
+        category,
+    }
+}
+
+/// Weapon icons are named after the weapon, e.g. `weapon_tdl-01_tg01` is a Stormblade (scythe) skill
+fn get_class_from_icon(icon: &str) -> Class {
+    let Some(weapon) = icon
+        .strip_prefix("weapon_")
+        .and_then(|rest| rest.split(['-', '_']).next())
+    else {
+        return Class::Unknown;
+    };
+    match weapon {
+        "tdl" => Class::Stormblade,
+        "mz" => Class::FrostMage,
+        "cq" => Class::WindKnight,
+        "fh" => Class::VerdantOracle,
+        "wr" => Class::HeavyGuardian,
+        "gj" => Class::Marksman,
+        "jd" => Class::ShieldKnight,
+        "jt" => Class::BeatPerformer,
+        _ => Class::Unknown,
+    }
+}
+
+fn get_category(name: &str, icon: Option<&str>) -> SkillCategory {
+    let name = name.strip_prefix("AI: ").unwrap_or(name);
+    if name.starts_with("Pet ") || name.starts_with("Pet-") {
+        return SkillCategory::Pet;
+    }
+    let Some(icon) = icon else {
+        return SkillCategory::Other;
+    };
+    if icon.starts_with("skill_aoyi") {
+        SkillCategory::Imagine
+    } else if !icon.starts_with("weapon") {
+        SkillCategory::Other
+    } else if icon.contains("_ult") {
+        SkillCategory::Ultimate
+    } else if icon.contains("atk") {
+        SkillCategory::Basic
+    } else {
+        SkillCategory::Special
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::opcodes_models::class::{Class, ClassSpec};
+    use crate::live::skill_metadata::{
+        SkillCategory, get_category, get_class_from_icon, get_skill_metadata,
+    };
+
+    #[test]
+    fn test_get_class_from_icon() {
+        assert!(matches!(
+            get_class_from_icon("weapon_tdl-01_tg01"),
+            Class::Stormblade
+        ));
+        assert!(matches!(
+            get_class_from_icon("weapon_cq_skill_atk"),
+            Class::WindKnight
+        ));
+        assert!(matches!(
+            get_class_from_icon("weapon_jd-01_ult_01"),
+            Class::ShieldKnight
+        ));
+        // Weapons without a playable class and non-weapon icons
+        assert!(matches!(
+            get_class_from_icon("weapon_sf-01_skill_atk01"),
+            Class::Unknown
+        ));
+        assert!(matches!(
+            get_class_from_icon("skill_aoyi_skill_icon_011"),
+            Class::Unknown
+        ));
+    }
+
+    #[test]
+    fn test_get_category() {
+        assert_eq!(
+            get_category("Judgment Cut", Some("weapon_tdl-01_skill_atk01")),
+            SkillCategory::Basic
+        );
+        assert_eq!(
+            get_category("Radiant Infusion", Some("weapon_jd-01_ult_01")),
+            SkillCategory::Ultimate
+        );
+        assert_eq!(
+            get_category("Iaido Slash", Some("weapon_tdl-01_tg01")),
+            SkillCategory::Special
+        );
+        assert_eq!(
+            get_category("Stunt! Fox Assault", Some("skill_aoyi_skill_icon_011")),
+            SkillCategory::Imagine
+        );
+        // Pets go by name, with or without the AI prefix, "Petrified" is no pet
+        assert_eq!(
+            get_category("AI: Pet Eagle Quick Spin", None),
+            SkillCategory::Pet
+        );
+        assert_eq!(
+            get_category("Pet - Foxen Pounce", Some("weapon_tdl-01_tg01")),
+            SkillCategory::Pet
+        );
+        assert_eq!(get_category("Petrified", None), SkillCategory::Other);
+        assert_eq!(
+            get_category("Buff", Some("buff_icon_01")),
+            SkillCategory::Other
+        );
+    }
+
+    #[test]
+    fn test_get_skill_metadata() {
+        // Spec-exclusive skills take the class from the spec table, shared ones from the icon
+        let iaido_slash = get_skill_metadata(1714);
+        assert_eq!(iaido_slash.class_spec, ClassSpec::Iaido);
+        assert!(matches!(iaido_slash.class, Class::Stormblade));
+
+        let judgment_cut = get_skill_metadata(1701);
+        assert_eq!(judgment_cut.class_spec, ClassSpec::Unknown);
+        assert!(matches!(judgment_cut.class, Class::Stormblade));
+        assert_eq!(judgment_cut.category, SkillCategory::Basic);
+        assert_eq!(
+            judgment_cut.icon.as_deref(),
+            Some("weapon_tdl-01_skill_atk01")
+        );
+    }
+}
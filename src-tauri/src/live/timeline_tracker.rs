@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+pub const TIMELINE_BUCKET_MS: u128 = 1000;
+
+/// Per-second value buckets per player, index 0 is the second the timeline started
+#[derive(Debug, Default, Clone)]
+pub struct TimelineTracker {
+    pub start_ms: Option<u128>, // first recorded hit
+    pub entity_uid_to_dmg: HashMap<i64, Vec<i64>>,
+    pub entity_uid_to_dmg_boss_only: HashMap<i64, Vec<i64>>,
+    pub entity_uid_to_heal: HashMap<i64, Vec<i64>>,
+}
+
+impl TimelineTracker {
+    pub fn record_dmg(&mut self, entity_uid: i64, value: i64, is_boss: bool, timestamp_ms: u128) {
+        let bucket = self.get_bucket(timestamp_ms);
+        add_to_bucket(&mut self.entity_uid_to_dmg, entity_uid, bucket, value);
+        if is_boss {
+            add_to_bucket(
+                &mut self.entity_uid_to_dmg_boss_only,
+                entity_uid,
+                bucket,
+                value,
+            );
+        }
+    }
+
+    pub fn record_heal(&mut self, entity_uid: i64, value: i64, timestamp_ms: u128) {
+        let bucket = self.get_bucket(timestamp_ms);
+        add_to_bucket(&mut self.entity_uid_to_heal, entity_uid, bucket, value);
+    }
+
+    /// Number of seconds covered so far
+    pub fn get_len(&self) -> usize {
+        self.entity_uid_to_dmg
+            .values()
+            .chain(self.entity_uid_to_heal.values())
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Value per second over the last `window_secs` seconds up to `end_ms`, summed over all given series.
+    /// The bucket `end_ms` falls in only holds what happened up to `end_ms` so it counts fully, the oldest
+    /// bucket only counts with the part of its second that's inside the window.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_rolling_value_per_sec<'a>(
+        &self,
+        series: impl IntoIterator<Item = &'a Vec<i64>>,
+        end_ms: u128,
+        window_secs: u32,
+    ) -> f64 {
+        let Some(start_ms) = self.start_ms else {
+            return 0.0;
+        };
+        let elapsed_ms = end_ms.saturating_sub(start_ms);
+        // Early in the fight the window only covers the time that happened so far, but at least a bucket
+        let window_ms = (u128::from(window_secs.max(1)) * TIMELINE_BUCKET_MS)
+            .min(elapsed_ms.max(TIMELINE_BUCKET_MS));
+        let window_start_ms = elapsed_ms.saturating_sub(window_ms);
+        let first_bucket = (window_start_ms / TIMELINE_BUCKET_MS) as usize;
+        let end_bucket = (elapsed_ms / TIMELINE_BUCKET_MS) as usize;
+        let first_bucket_weight = ((first_bucket as u128 + 1) * TIMELINE_BUCKET_MS
+            - window_start_ms) as f64
+            / TIMELINE_BUCKET_MS as f64;
+        let sum: f64 = series
+            .into_iter()
+            .map(|buckets| {
+                let first_value = buckets.get(first_bucket).copied().unwrap_or_default();
+                let rest_sum: i64 = buckets
+                    .iter()
+                    .take(end_bucket + 1)
+                    .skip(first_bucket + 1)
+                    .sum();
+                first_value as f64 * first_bucket_weight + rest_sum as f64
+            })
+            .sum();
+        sum / (window_ms as f64 / 1000.0)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn get_bucket(&mut self, timestamp_ms: u128) -> usize {
+        let start_ms = *self.start_ms.get_or_insert(timestamp_ms);
+        (timestamp_ms.saturating_sub(start_ms) / TIMELINE_BUCKET_MS) as usize
+    }
+}
+
+fn add_to_bucket(
+    entity_uid_to_buckets: &mut HashMap<i64, Vec<i64>>,
+    entity_uid: i64,
+    bucket: usize,
+    value: i64,
+) {
+    let buckets = entity_uid_to_buckets.entry(entity_uid).or_default();
+    if buckets.len() <= bucket {
+        buckets.resize(bucket + 1, 0);
+    }
+    buckets[bucket] += value;
+}
+
+/// Merges every `bucket_secs` seconds into one value per second (average over the merged bucket)
+#[allow(clippy::cast_precision_loss)]
+pub fn downsample(buckets: &[i64], len: usize, bucket_secs: usize) -> Vec<f64> {
+    let bucket_secs = bucket_secs.max(1);
+    (0..len.div_ceil(bucket_secs))
+        .map(|i| {
+            let start = i * bucket_secs;
+            let end = (start + bucket_secs).min(len);
+            let sum: i64 = buckets.iter().skip(start).take(end - start).sum();
+            sum as f64 / (end - start) as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::live::timeline_tracker::TimelineTracker;
+
+    const PLAYER_UID: i64 = 7;
+
+    fn rolling_dps(timeline_tracker: &TimelineTracker, end_ms: u128, window_secs: u32) -> f64 {
+        timeline_tracker.get_rolling_value_per_sec(
+            timeline_tracker.entity_uid_to_dmg.values(),
+            end_ms,
+            window_secs,
+        )
+    }
+
+    #[test]
+    fn test_rolling_window_keeps_moving() {
+        let mut timeline_tracker = TimelineTracker::default();
+        for second in 0..10 {
+            timeline_tracker.record_dmg(PLAYER_UID, 1_000, false, second * 1_000);
+        }
+        assert!((rolling_dps(&timeline_tracker, 10_000, 5) - 1_000.0).abs() < f64::EPSILON);
+
+        // Half of the oldest second is still inside the window
+        assert!((rolling_dps(&timeline_tracker, 12_500, 5) - 500.0).abs() < f64::EPSILON);
+
+        // Falls to zero once the fight stopped for longer than the window
+        assert!(rolling_dps(&timeline_tracker, 20_000, 5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_rolling_window_early_in_fight() {
+        let mut timeline_tracker = TimelineTracker::default();
+        assert!(rolling_dps(&timeline_tracker, 1_000, 5).abs() < f64::EPSILON);
+
+        timeline_tracker.record_dmg(PLAYER_UID, 1_000, false, 0);
+        // The window never gets shorter than a bucket
+        assert!((rolling_dps(&timeline_tracker, 0, 5) - 1_000.0).abs() < f64::EPSILON);
+        assert!((rolling_dps(&timeline_tracker, 2_500, 5) - 400.0).abs() < f64::EPSILON);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::live::clock::{Clock, WallClock};
 use crate::packets;
 use crate::packets::opcodes::Pkt;
 use crate::packets::packet_process::process_packet;
@@ -17,9 +18,10 @@ static RESTART_SENDER: OnceCell<watch::Sender<bool>> = OnceCell::new();
 // Delay between handle cleanup and recreation to allow kernel cleanup
 const HANDLE_CLEANUP_DELAY_MS: u64 = 500;
 
-pub fn start_capture() -> tokio::sync::mpsc::Receiver<(packets::opcodes::Pkt, Vec<u8>)> {
+// Packets come with the local time they were captured at, in ms
+pub fn start_capture() -> tokio::sync::mpsc::Receiver<(packets::opcodes::Pkt, Vec<u8>, u128)> {
     let (packet_sender, packet_receiver) =
-        tokio::sync::mpsc::channel::<(packets::opcodes::Pkt, Vec<u8>)>(1);
+        tokio::sync::mpsc::channel::<(packets::opcodes::Pkt, Vec<u8>, u128)>(1);
     let (restart_sender, mut restart_receiver) = watch::channel(false);
     RESTART_SENDER.set(restart_sender.clone()).ok();
     tauri::async_runtime::spawn(async move {
@@ -40,7 +42,7 @@ pub fn start_capture() -> tokio::sync::mpsc::Receiver<(packets::opcodes::Pkt, Ve
 
 #[allow(clippy::too_many_lines)]
 async fn read_packets(
-    packet_sender: &tokio::sync::mpsc::Sender<(packets::opcodes::Pkt, Vec<u8>)>,
+    packet_sender: &tokio::sync::mpsc::Sender<(packets::opcodes::Pkt, Vec<u8>, u128)>,
     restart_receiver: &mut watch::Receiver<bool>,
 ) {
     let windivert = match WinDivert::network(
@@ -65,6 +67,7 @@ async fn read_packets(
     // Note: windivert.recv() is blocking, so we can't check restart signal while it's blocking.
     // The restart will be detected after the next packet is received.
     while let Ok(packet) = windivert.recv(Some(&mut windivert_buffer)) {
+        let captured_ms = WallClock.now_ms();
         // info!("{}", line!());
         let Ok(network_slices) = SlicedPacket::from_ip(packet.data.as_ref()) else {
             continue; // if it's not ip, go next packet
@@ -132,7 +135,11 @@ async fn read_packets(
                                                         + tcp_payload_reader.len(),
                                                 );
                                                 if let Err(err) = packet_sender
-                                                    .send((Pkt::ServerChangeInfo, Vec::new()))
+                                                    .send((
+                                                        Pkt::ServerChangeInfo,
+                                                        Vec::new(),
+                                                        captured_ms,
+                                                    ))
                                                     .await
                                                 {
                                                     debug!("Failed to send packet: {err}");
@@ -172,7 +179,7 @@ async fn read_packets(
                         tcp_packet.sequence_number() as usize + tcp_payload.len(),
                     );
                     if let Err(err) = packet_sender
-                        .send((Pkt::ServerChangeInfo, Vec::new()))
+                        .send((Pkt::ServerChangeInfo, Vec::new(), captured_ms))
                         .await
                     {
                         debug!("Failed to send packet: {err}");
@@ -250,7 +257,12 @@ async fn read_packets(
                     line!(),
                     packet_size
                 );
-                process_packet(BinaryReader::from(packet), packet_sender.clone()).await;
+                process_packet(
+                    BinaryReader::from(packet),
+                    packet_sender.clone(),
+                    captured_ms,
+                )
+                .await;
             }
         }
         if *restart_receiver.borrow() {
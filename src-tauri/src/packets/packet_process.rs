@@ -5,7 +5,8 @@ use log::debug;
 
 pub async fn process_packet(
     mut packets_reader: BinaryReader,
-    packet_sender: tokio::sync::mpsc::Sender<(packets::opcodes::Pkt, Vec<u8>)>,
+    packet_sender: tokio::sync::mpsc::Sender<(packets::opcodes::Pkt, Vec<u8>, u128)>,
+    captured_ms: u128, // when the TCP segment completing these packets was captured
 ) {
     while packets_reader.remaining() > 0 {
         let packet_size = match packets_reader.peek_u32() {
@@ -90,7 +91,10 @@ pub async fn process_packet(
                     }
                 };
 
-                if let Err(err) = packet_sender.send((method_id, tcp_fragment_vec)).await {
+                if let Err(err) = packet_sender
+                    .send((method_id, tcp_fragment_vec, captured_ms))
+                    .await
+                {
                     debug!("Failed to send packet: {err}");
                 }
             }
@@ -208,16 +212,46 @@ mod tests {
     use crate::packets::opcodes::Pkt;
     use crate::packets::packet_process::process_packet;
     use crate::packets::utils::BinaryReader;
+    use blueprotobuf_lib::blueprotobuf;
+    use prost::Message;
 
     #[tokio::test]
     async fn test_add() {
         use std::fs;
-        let (packet_sender, _) = tokio::sync::mpsc::channel::<(Pkt, Vec<u8>)>(1);
+        let (packet_sender, _) = tokio::sync::mpsc::channel::<(Pkt, Vec<u8>, u128)>(1);
         let filename = "src/packets/test_add_packet.json";
         let v: Vec<u8> = serde_json::from_str(
             &fs::read_to_string(filename).expect(&format!("Failed to open {filename}")),
         )
         .expect("Invalid JSON in test_packet.json");
-        process_packet(BinaryReader::from(v), packet_sender).await;
+        process_packet(BinaryReader::from(v), packet_sender, 0).await;
+    }
+
+    #[tokio::test]
+    async fn test_sync_dungeon_data() {
+        use std::fs;
+        let (packet_sender, mut packet_receiver) =
+            tokio::sync::mpsc::channel::<(Pkt, Vec<u8>, u128)>(16);
+        let filename = "src/packets/test_add_packet.json";
+        let v: Vec<u8> = serde_json::from_str(
+            &fs::read_to_string(filename).unwrap_or_else(|_| panic!("Failed to open {filename}")),
+        )
+        .expect("Invalid JSON in test_packet.json");
+        process_packet(BinaryReader::from(v), packet_sender, 0).await;
+
+        // The recorded packet carries a method 0x17 notify, it has to decode as SyncDungeonData
+        let mut sync_dungeon_data = None;
+        while let Ok((op, data, _)) = packet_receiver.try_recv() {
+            if matches!(op, Pkt::SyncDungeonData) {
+                sync_dungeon_data =
+                    Some(blueprotobuf::SyncDungeonData::decode(data.as_slice()).unwrap());
+            }
+        }
+        let dungeon_sync_data = sync_dungeon_data
+            .expect("no SyncDungeonData in test_add_packet.json")
+            .v_data
+            .unwrap();
+        assert_eq!(dungeon_sync_data.scene_uuid, Some(1));
+        assert!(dungeon_sync_data.flow_info.is_some());
     }
 }
@@ -4,7 +4,29 @@
  * and uses the appropriate communication method
  */
 
-import type { HeaderInfo, PlayersWindow, SkillsWindow, Result } from './bindings';
+import type {
+	BodyPartsWindow,
+	BossesWindow,
+	BuffsWindow,
+	CharacterProfile,
+	CooldownsWindow,
+	DeathRecapWindow,
+	DeathsWindow,
+	DungeonRunsWindow,
+	EncounterHistoryWindow,
+	HatesWindow,
+	HeaderInfo,
+	HistoryQuery,
+	HistoryWindow,
+	LocationInfo,
+	PlayersWindow,
+	SkillGrouping,
+	SkillsWindow,
+	StatType,
+	TargetsWindow,
+	TimelineWindow,
+	Result
+} from './bindings';
 
 // Check if we're running in Tauri environment
 function isTauriEnvironment(): boolean {
@@ -115,11 +137,15 @@ class UniversalAPI {
 	}
 
 	// Skill Windows
-	async getDpsSkillWindow(playerUidStr: string): Promise<Result<SkillsWindow, string>> {
+	async getDpsSkillWindow(
+		playerUidStr: string,
+		grouping: SkillGrouping = 'skill'
+	): Promise<Result<SkillsWindow, string>> {
 		if (this.isTauri) {
 			try {
 				const data = await this.tauriInvoke<SkillsWindow>('get_dps_skill_window', {
-					playerUidStr
+					playerUidStr,
+					grouping
 				});
 				return { status: 'ok', data };
 			} catch (error) {
@@ -127,7 +153,9 @@ class UniversalAPI {
 			}
 		} else {
 			try {
-				const data = await this.httpFetch<SkillsWindow>(`dps-skill-window/${playerUidStr}`);
+				const data = await this.httpFetch<SkillsWindow>(
+					`dps-skill-window/${playerUidStr}?grouping=${grouping}`
+				);
 				return { status: 'ok', data };
 			} catch (error) {
 				return { status: 'error', error: String(error) };
@@ -135,11 +163,15 @@ class UniversalAPI {
 		}
 	}
 
-	async getDpsBossOnlySkillWindow(playerUidStr: string): Promise<Result<SkillsWindow, string>> {
+	async getDpsBossOnlySkillWindow(
+		playerUidStr: string,
+		grouping: SkillGrouping = 'skill'
+	): Promise<Result<SkillsWindow, string>> {
 		if (this.isTauri) {
 			try {
 				const data = await this.tauriInvoke<SkillsWindow>('get_dps_boss_only_skill_window', {
-					playerUidStr
+					playerUidStr,
+					grouping
 				});
 				return { status: 'ok', data };
 			} catch (error) {
@@ -148,7 +180,7 @@ class UniversalAPI {
 		} else {
 			try {
 				const data = await this.httpFetch<SkillsWindow>(
-					`dps-boss-only-skill-window/${playerUidStr}`
+					`dps-boss-only-skill-window/${playerUidStr}?grouping=${grouping}`
 				);
 				return { status: 'ok', data };
 			} catch (error) {
@@ -157,11 +189,15 @@ class UniversalAPI {
 		}
 	}
 
-	async getHealSkillWindow(playerUidStr: string): Promise<Result<SkillsWindow, string>> {
+	async getHealSkillWindow(
+		playerUidStr: string,
+		grouping: SkillGrouping = 'skill'
+	): Promise<Result<SkillsWindow, string>> {
 		if (this.isTauri) {
 			try {
 				const data = await this.tauriInvoke<SkillsWindow>('get_heal_skill_window', {
-					playerUidStr
+					playerUidStr,
+					grouping
 				});
 				return { status: 'ok', data };
 			} catch (error) {
@@ -169,7 +205,9 @@ class UniversalAPI {
 			}
 		} else {
 			try {
-				const data = await this.httpFetch<SkillsWindow>(`heal-skill-window/${playerUidStr}`);
+				const data = await this.httpFetch<SkillsWindow>(
+					`heal-skill-window/${playerUidStr}?grouping=${grouping}`
+				);
 				return { status: 'ok', data };
 			} catch (error) {
 				return { status: 'error', error: String(error) };
@@ -195,6 +233,135 @@ class UniversalAPI {
 		}
 	}
 
+	// Buff Window
+	async getBuffWindow(): Promise<BuffsWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<BuffsWindow>('get_buff_window');
+		} else {
+			return this.httpFetch<BuffsWindow>('buff-window');
+		}
+	}
+
+	// Cooldowns (local player only)
+	async getCooldownWindow(): Promise<CooldownsWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<CooldownsWindow>('get_cooldown_window');
+		} else {
+			return this.httpFetch<CooldownsWindow>('cooldown-window');
+		}
+	}
+
+	// Hate/aggro (local player only)
+	async getHateWindow(): Promise<HatesWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<HatesWindow>('get_hate_window');
+		} else {
+			return this.httpFetch<HatesWindow>('hate-window');
+		}
+	}
+
+	// Monsters engaged this encounter
+	async getTargets(): Promise<TargetsWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<TargetsWindow>('get_targets');
+		} else {
+			return this.httpFetch<TargetsWindow>('targets');
+		}
+	}
+
+	// Body parts / breaks
+	async getBodyPartWindow(): Promise<BodyPartsWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<BodyPartsWindow>('get_body_part_window');
+		} else {
+			return this.httpFetch<BodyPartsWindow>('body-part-window');
+		}
+	}
+
+	// Local player's gear and build
+	async getCharacterProfile(): Promise<Result<CharacterProfile, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<CharacterProfile>('get_character_profile')
+				: await this.httpFetch<CharacterProfile>('character-profile');
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	// Current map, line and dungeon
+	async getLocation(): Promise<Result<LocationInfo, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<LocationInfo>('get_location')
+				: await this.httpFetch<LocationInfo>('location');
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	// Deaths
+	async getDeathsWindow(): Promise<DeathsWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<DeathsWindow>('get_deaths_window');
+		} else {
+			return this.httpFetch<DeathsWindow>('deaths-window');
+		}
+	}
+
+	async getDeathRecap(
+		playerUidStr: string,
+		maxEvents: number
+	): Promise<Result<DeathRecapWindow, string>> {
+		if (this.isTauri) {
+			try {
+				const data = await this.tauriInvoke<DeathRecapWindow>('get_death_recap', {
+					playerUidStr,
+					maxEvents
+				});
+				return { status: 'ok', data };
+			} catch (error) {
+				return { status: 'error', error: String(error) };
+			}
+		} else {
+			try {
+				const data = await this.httpFetch<DeathRecapWindow>(
+					`death-recap/${playerUidStr}?maxEvents=${maxEvents}`
+				);
+				return { status: 'ok', data };
+			} catch (error) {
+				return { status: 'error', error: String(error) };
+			}
+		}
+	}
+
+	// Boss Window
+	async getBossWindow(phaseThresholdsPct: number[] | null = null): Promise<BossesWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<BossesWindow>('get_boss_window', { phaseThresholdsPct });
+		} else {
+			const query = phaseThresholdsPct ? `?phaseThresholds=${phaseThresholdsPct.join(',')}` : '';
+			return this.httpFetch<BossesWindow>(`boss-window${query}`);
+		}
+	}
+
+	async getTimeline(
+		statType: StatType = 'dmg',
+		bucketSecs: number | null = null,
+		maxPoints: number | null = null
+	): Promise<TimelineWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<TimelineWindow>('get_timeline', { statType, bucketSecs, maxPoints });
+		} else {
+			const params = new URLSearchParams({ statType });
+			if (bucketSecs !== null) params.set('bucketSecs', String(bucketSecs));
+			if (maxPoints !== null) params.set('maxPoints', String(maxPoints));
+			return this.httpFetch<TimelineWindow>(`timeline?${params}`);
+		}
+	}
+
 	// Encounter control
 	async resetEncounter(): Promise<void> {
 		if (this.isTauri) {
@@ -204,6 +371,167 @@ class UniversalAPI {
 		}
 	}
 
+	// Encounter history
+	async getEncounterHistory(): Promise<EncounterHistoryWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<EncounterHistoryWindow>('get_encounter_history');
+		} else {
+			return this.httpFetch<EncounterHistoryWindow>('encounters');
+		}
+	}
+
+	// Dungeon runs, each spanning several encounters
+	async getDungeonRuns(): Promise<DungeonRunsWindow> {
+		if (this.isTauri) {
+			return this.tauriInvoke<DungeonRunsWindow>('get_dungeon_runs');
+		} else {
+			return this.httpFetch<DungeonRunsWindow>('dungeon-runs');
+		}
+	}
+
+	async getEncounterPlayerWindow(
+		encounterId: number,
+		statType: StatType
+	): Promise<Result<PlayersWindow, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<PlayersWindow>('get_encounter_player_window', {
+						encounterId,
+						statType
+					})
+				: await this.httpFetch<PlayersWindow>(
+						`encounters/${encounterId}/player-window?statType=${statType}`
+					);
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	async getEncounterSkillWindow(
+		encounterId: number,
+		playerUidStr: string,
+		statType: StatType,
+		grouping: SkillGrouping = 'skill'
+	): Promise<Result<SkillsWindow, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<SkillsWindow>('get_encounter_skill_window', {
+						encounterId,
+						playerUidStr,
+						statType,
+						grouping
+					})
+				: await this.httpFetch<SkillsWindow>(
+						`encounters/${encounterId}/skill-window/${playerUidStr}?statType=${statType}&grouping=${grouping}`
+					);
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	// Saved encounter history (database)
+	async getHistory(query: Partial<HistoryQuery> = {}): Promise<Result<HistoryWindow, string>> {
+		try {
+			if (this.isTauri) {
+				const data = await this.tauriInvoke<HistoryWindow>('get_history', { query });
+				return { status: 'ok', data };
+			}
+			const params = new URLSearchParams();
+			for (const [key, value] of Object.entries(query)) {
+				if (value !== null && value !== undefined) {
+					params.set(key, String(value));
+				}
+			}
+			const data = await this.httpFetch<HistoryWindow>(`history?${params}`);
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	async getHistoryPlayerWindow(
+		encounterId: number,
+		statType: StatType
+	): Promise<Result<PlayersWindow, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<PlayersWindow>('get_history_player_window', {
+						encounterId,
+						statType
+					})
+				: await this.httpFetch<PlayersWindow>(
+						`history/${encounterId}/player-window?statType=${statType}`
+					);
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	async getHistorySkillWindow(
+		encounterId: number,
+		playerUidStr: string,
+		statType: StatType,
+		grouping: SkillGrouping = 'skill'
+	): Promise<Result<SkillsWindow, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<SkillsWindow>('get_history_skill_window', {
+						encounterId,
+						playerUidStr,
+						statType,
+						grouping
+					})
+				: await this.httpFetch<SkillsWindow>(
+						`history/${encounterId}/skill-window/${playerUidStr}?statType=${statType}&grouping=${grouping}`
+					);
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	async getHistoryCharacterProfile(
+		encounterId: number
+	): Promise<Result<CharacterProfile | null, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<CharacterProfile | null>('get_history_character_profile', {
+						encounterId
+					})
+				: await this.httpFetch<CharacterProfile | null>(
+						`history/${encounterId}/character-profile`
+					);
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	async deleteHistoryEncounter(encounterId: number): Promise<Result<boolean, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<boolean>('delete_history_encounter', { encounterId })
+				: await this.httpFetch<boolean>(`history/${encounterId}`, { method: 'DELETE' });
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
+	async clearHistory(): Promise<Result<null, string>> {
+		try {
+			const data = this.isTauri
+				? await this.tauriInvoke<null>('clear_history')
+				: await this.httpFetch<null>('history', { method: 'DELETE' });
+			return { status: 'ok', data };
+		} catch (error) {
+			return { status: 'error', error: String(error) };
+		}
+	}
+
 	async togglePauseEncounter(): Promise<void> {
 		if (this.isTauri) {
 			await this.tauriInvoke<void>('toggle_pause_encounter');
@@ -256,6 +584,28 @@ class UniversalAPI {
 		}
 	}
 
+	async setEncounterInactivityTimeout(timeoutSecs: number): Promise<void> {
+		if (this.isTauri) {
+			await this.tauriInvoke<void>('set_encounter_inactivity_timeout', { timeoutSecs });
+		} else {
+			await this.httpFetch<void>('set-encounter-inactivity-timeout', {
+				method: 'POST',
+				body: JSON.stringify({ timeoutSecs })
+			});
+		}
+	}
+
+	async setRollingWindowSecs(windowSecs: number): Promise<void> {
+		if (this.isTauri) {
+			await this.tauriInvoke<void>('set_rolling_window_secs', { windowSecs });
+		} else {
+			await this.httpFetch<void>('set-rolling-window-secs', {
+				method: 'POST',
+				body: JSON.stringify({ windowSecs })
+			});
+		}
+	}
+
 	// Utility
 	isRunningInTauri(): boolean {
 		return this.isTauri;
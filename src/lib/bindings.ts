@@ -14,6 +14,38 @@ async disableBlur() : Promise<void> {
 async copySyncContainerData() : Promise<void> {
     await TAURI_INVOKE("copy_sync_container_data");
 },
+async dumpEventLog() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("dump_event_log") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async dumpEncounterEventLog(encounterId: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("dump_encounter_event_log", { encounterId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getCharacterProfile() : Promise<Result<CharacterProfile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_character_profile") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getLocation() : Promise<Result<LocationInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_location") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getHeaderInfo() : Promise<Result<HeaderInfo, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_header_info") };
@@ -25,9 +57,9 @@ async getHeaderInfo() : Promise<Result<HeaderInfo, string>> {
 async getDpsPlayerWindow() : Promise<PlayersWindow> {
     return await TAURI_INVOKE("get_dps_player_window");
 },
-async getDpsSkillWindow(playerUidStr: string) : Promise<Result<SkillsWindow, string>> {
+async getDpsSkillWindow(playerUidStr: string, grouping: SkillGrouping | null) : Promise<Result<SkillsWindow, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_dps_skill_window", { playerUidStr }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_dps_skill_window", { playerUidStr, grouping }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -36,9 +68,9 @@ async getDpsSkillWindow(playerUidStr: string) : Promise<Result<SkillsWindow, str
 async getDpsBossOnlyPlayerWindow() : Promise<PlayersWindow> {
     return await TAURI_INVOKE("get_dps_boss_only_player_window");
 },
-async getDpsBossOnlySkillWindow(playerUidStr: string) : Promise<Result<SkillsWindow, string>> {
+async getDpsBossOnlySkillWindow(playerUidStr: string, grouping: SkillGrouping | null) : Promise<Result<SkillsWindow, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_dps_boss_only_skill_window", { playerUidStr }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_dps_boss_only_skill_window", { playerUidStr, grouping }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -47,9 +79,114 @@ async getDpsBossOnlySkillWindow(playerUidStr: string) : Promise<Result<SkillsWin
 async getHealPlayerWindow() : Promise<PlayersWindow> {
     return await TAURI_INVOKE("get_heal_player_window");
 },
-async getHealSkillWindow(playerUidStr: string) : Promise<Result<SkillsWindow, string>> {
+async getHealSkillWindow(playerUidStr: string, grouping: SkillGrouping | null) : Promise<Result<SkillsWindow, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_heal_skill_window", { playerUidStr, grouping }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getBuffWindow() : Promise<BuffsWindow> {
+    return await TAURI_INVOKE("get_buff_window");
+},
+async getDeathsWindow() : Promise<DeathsWindow> {
+    return await TAURI_INVOKE("get_deaths_window");
+},
+async getCooldownWindow() : Promise<CooldownsWindow> {
+    return await TAURI_INVOKE("get_cooldown_window");
+},
+async getHateWindow() : Promise<HatesWindow> {
+    return await TAURI_INVOKE("get_hate_window");
+},
+async getTargets() : Promise<TargetsWindow> {
+    return await TAURI_INVOKE("get_targets");
+},
+async getBodyPartWindow() : Promise<BodyPartsWindow> {
+    return await TAURI_INVOKE("get_body_part_window");
+},
+async getDeathRecap(playerUidStr: string, maxEvents: number) : Promise<Result<DeathRecapWindow, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_death_recap", { playerUidStr, maxEvents }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getBossWindow(phaseThresholdsPct: number[] | null) : Promise<BossesWindow> {
+    return await TAURI_INVOKE("get_boss_window", { phaseThresholdsPct });
+},
+async getTimeline(statType: StatType, bucketSecs: number | null, maxPoints: number | null) : Promise<TimelineWindow> {
+    return await TAURI_INVOKE("get_timeline", { statType, bucketSecs, maxPoints });
+},
+async setEncounterInactivityTimeout(timeoutSecs: number) : Promise<void> {
+    await TAURI_INVOKE("set_encounter_inactivity_timeout", { timeoutSecs });
+},
+async getEncounterHistory() : Promise<EncounterHistoryWindow> {
+    return await TAURI_INVOKE("get_encounter_history");
+},
+async getDungeonRuns() : Promise<DungeonRunsWindow> {
+    return await TAURI_INVOKE("get_dungeon_runs");
+},
+async getEncounterPlayerWindow(encounterId: number, statType: StatType) : Promise<Result<PlayersWindow, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_encounter_player_window", { encounterId, statType }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getEncounterSkillWindow(encounterId: number, playerUidStr: string, statType: StatType, grouping: SkillGrouping | null) : Promise<Result<SkillsWindow, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_heal_skill_window", { playerUidStr }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_encounter_skill_window", { encounterId, playerUidStr, statType, grouping }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistory(query: HistoryQuery) : Promise<Result<HistoryWindow, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryPlayerWindow(encounterId: number, statType: StatType) : Promise<Result<PlayersWindow, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_player_window", { encounterId, statType }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistorySkillWindow(encounterId: number, playerUidStr: string, statType: StatType, grouping: SkillGrouping | null) : Promise<Result<SkillsWindow, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_skill_window", { encounterId, playerUidStr, statType, grouping }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistoryCharacterProfile(encounterId: number) : Promise<Result<CharacterProfile | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_history_character_profile", { encounterId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteHistoryEncounter(encounterId: number) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_history_encounter", { encounterId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearHistory() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_history") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -77,6 +214,9 @@ async getTestSkillWindow(playerUid: string) : Promise<Result<SkillsWindow, strin
 },
 async setBptimerEnabled(enabled: boolean) : Promise<void> {
     await TAURI_INVOKE("set_bptimer_enabled", { enabled });
+},
+async setRollingWindowSecs(windowSecs: number) : Promise<void> {
+    await TAURI_INVOKE("set_rolling_window_secs", { windowSecs });
 }
 }
 
@@ -90,11 +230,56 @@ async setBptimerEnabled(enabled: boolean) : Promise<void> {
 
 /** user-defined types **/
 
-export type HeaderInfo = { totalDps: number; totalDmg: number; elapsedMs: number; timeLastCombatPacketMs: number }
-export type PlayerRow = { uid: number; abilityScore: number; className: string; classSpecName: string; name: string; totalValue: number; valuePerSec: number; valuePct: number; critRate: number; critValueRate: number; luckyRate: number; luckyValueRate: number; hits: number; hitsPerMinute: number }
-export type PlayersWindow = { playerRows: PlayerRow[]; localPlayerUid: number; topValue: number }
-export type SkillRow = { uid: number; name: string; totalValue: number; valuePerSec: number; valuePct: number; critRate: number; critValueRate: number; luckyRate: number; luckyValueRate: number; hits: number; hitsPerMinute: number }
-export type SkillsWindow = { inspectedPlayer: PlayerRow; skillRows: SkillRow[]; localPlayerUid: number; topValue: number }
+export type BodyPartEntityRow = { uid: number; name: string; monsterId: number; isBoss: boolean; totalValue: number; partRows: BodyPartRow[] }
+export type BodyPartPlayerRow = { uid: number; name: string; className: string; classSpecName: string; totalValue: number; valuePct: number; hits: number }
+export type BodyPartRow = { partId: number; state: string; currHp: number; maxHp: number; hpPct: number; totalValue: number; hits: number; brokenMs: number; brokenElapsedMs: number; stateChanges: BodyPartStateChange[]; playerRows: BodyPartPlayerRow[] }
+export type BodyPartStateChange = { timestampMs: number; elapsedMs: number; state: string }
+export type BodyPartsWindow = { entityRows: BodyPartEntityRow[] }
+export type BossPhaseRow = { index: number; startThresholdPct: number; startMs: number; endMs: number; durationMs: number }
+export type BossRow = { uid: number; monsterId: number; name: string; currHp: number; maxHp: number; hpPct: number; isDead: boolean; recentDps: number; projectedKillMs: number; phaseRows: BossPhaseRow[]; hpTimeline: HpPoint[] }
+export type BossesWindow = { bossRows: BossRow[] }
+export type BuffEntityRow = { uid: number; name: string; isBoss: boolean; buffRows: BuffRow[] }
+export type BuffRow = { baseId: number; name: string; isActive: boolean; uptimeMs: number; uptimePct: number; applications: number; refreshes: number; removals: number; currLayer: number; maxLayer: number; sourceUids: number[] }
+export type BuffsWindow = { entityRows: BuffEntityRow[]; localPlayerUid: number }
+export type CharacterProfile = { uid: number; snapshotMs: number | null; name: string | null; level: number | null; abilityScore: number | null; professionId: number | null; className: string; assistProfessionIds: number[]; curHp: number | null; maxHp: number | null; equips: EquipProfile[]; professions: ProfessionProfile[]; skillSlots: SkillSlotProfile[]; mods: ModProfile[] }
+export type CooldownRow = { uid: number; name: string; isFightResource: boolean; casts: number; castsPerMinute: number; cooldownMs: number; readyTimeMs: number; readyPct: number }
+export type CooldownsWindow = { localPlayerUid: number; elapsedMs: number; totalCasts: number; castsPerMinute: number; cooldownRows: CooldownRow[] }
+export type DeathRecap = { death: DeathRow; incomingRows: IncomingRow[] }
+export type DeathRecapWindow = { playerUid: number; playerName: string; deathRecaps: DeathRecap[] }
+export type DeathRow = { playerUid: number; playerName: string; timestampMs: number; elapsedMs: number; killerUid: number; killerName: string; killerSkillName: string }
+export type DeathsWindow = { deathRows: DeathRow[]; localPlayerUid: number }
+export type DungeonPlayerRow = { uid: number; name: string; className: string; level: number; abilityScore: number }
+export type DungeonRunRow = { sceneUuid: number; isActive: boolean; mapId: number | null; levelMapId: number | null; dungeonGuid: string | null; difficulty: number; state: string; result: number; startMs: number; endMs: number; clearTimeMs: number; passTime: number; totalScore: number; masterModeScore: number; affixIds: number[]; playerRows: DungeonPlayerRow[]; encounterIds: number[]; historyEncounterIds: number[] }
+export type DungeonRunsWindow = { runRows: DungeonRunRow[] }
+export type EncounterHistoryWindow = { encounterRows: EncounterRow[] }
+export type EncounterRow = { id: number; endReason: string; timeFightStartMs: number; timeLastCombatPacketMs: number; elapsedMs: number; totalDmg: number; totalDps: number; totalHeal: number; playerCount: number; bossNames: string[]; mapId: number | null; lineId: number | null; levelMapId: number | null; dungeonGuid: string | null; localPlayerName: string; topPlayerName: string }
+export type EquipProfile = { slot: number; itemUuid: number | null; refineLevel: number; enchantItemTypeId: number | null; enchantLevel: number | null }
+export type HateFlipRow = { timestampMs: number; elapsedMs: number; entityUid: number; entityName: string; isBoss: boolean; isGained: boolean }
+export type HateRow = { uid: number; name: string; monsterId: number; isBoss: boolean; isHolding: boolean; pulls: number; drops: number; holdTimeMs: number; holdPct: number }
+export type HatesWindow = { localPlayerUid: number; lastBossPullMs: number; hateRows: HateRow[]; flipRows: HateFlipRow[] }
+export type HeaderInfo = { totalDps: number; totalDmg: number; elapsedMs: number; timeLastCombatPacketMs: number; rollingDps: number; rollingWindowSecs: number; lastBossPullMs: number }
+export type HistoryQuery = { monsterId: number | null; mapId: number | null; lineId: number | null; levelMapId: number | null; dungeonGuid: string | null; fromMs: number | null; toMs: number | null; localPlayerUid: string | null; localPlayerName: string | null; className: string | null; classSpecName: string | null; minDurationMs: number | null; sortBy: HistorySortBy | null; sortAscending: boolean | null; page: number | null; pageSize: number | null }
+export type HistorySortBy = "date" | "duration" | "totalDmg" | "totalDps"
+export type HistoryWindow = { encounterRows: EncounterRow[]; totalCount: number; page: number; pageSize: number }
+export type HpPoint = { timestampMs: number; hpPct: number }
+export type IncomingRow = { timestampMs: number; msBeforeDeath: number; sourceUid: number; sourceName: string; skillUid: number; skillName: string; value: number; isHeal: boolean; isCrit: boolean; isLucky: boolean; isKillingBlow: boolean }
+export type Location = { mapId: number | null; channelId: number | null; levelMapId: number | null; sceneGuid: string | null; dungeonGuid: string | null; planeId: number | null; lineId: number | null }
+export type LocationInfo = { location: Location; isDungeon: boolean; enteredMs: number }
+export type ModProfile = { slot: number; modUuid: number; partIds: number[]; successRate: number | null }
+export type PassiveRow = { uid: number; name: string; procs: number; procsPerMinute: number; totalValue: number; valuePct: number; hits: number }
+export type PlayerRow = { uid: number; abilityScore: number; className: string; classSpecName: string; name: string; totalValue: number; valuePerSec: number; valuePct: number; critRate: number; critValueRate: number; luckyRate: number; luckyValueRate: number; rainbowRate: number; hits: number; hitsPerMinute: number; activeDps: number; activeTimeMs: number; rollingValuePerSec: number }
+export type PlayersWindow = { playerRows: PlayerRow[]; localPlayerUid: number; topValue: number; rollingWindowSecs: number; groupRollingValuePerSec: number }
+export type ProfessionProfile = { professionId: number; level: number | null; activeSkillIds: number[] }
+export type SkillCategory = "basic" | "special" | "ultimate" | "imagine" | "pet" | "other"
+export type SkillGrouping = "skill" | "source"
+export type SkillRow = { uid: number; name: string; isSummon: boolean; summonUid: number; icon: string; className: string; classSpecName: string; category: SkillCategory; totalValue: number; valuePerSec: number; valuePct: number; critRate: number; critValueRate: number; luckyRate: number; luckyValueRate: number; rainbowRate: number; hits: number; hitsPerMinute: number; activeDps: number; activeTimeMs: number }
+export type SkillSlotProfile = { slotId: number; skillId: number | null }
+export type SkillsWindow = { inspectedPlayer: PlayerRow; skillRows: SkillRow[]; localPlayerUid: number; topValue: number; summonValue: number; passiveRows: PassiveRow[] }
+export type StatType = "dmg" | "dmgBossOnly" | "heal"
+export type TargetRow = { uid: number; name: string; monsterId: number; isBoss: boolean; currHp: number; maxHp: number; hpPct: number; isDeparted: boolean; takenValue: number; takenHits: number; takenPct: number }
+export type TargetsWindow = { targetRows: TargetRow[] }
+export type TimelineRow = { uid: number; name: string; className: string; classSpecName: string; totalValue: number; points: number[] }
+export type TimelineWindow = { startMs: number; bucketMs: number; groupPoints: number[]; timelineRows: TimelineRow[] }
 
 /** tauri-specta globals **/
 
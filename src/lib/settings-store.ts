@@ -6,6 +6,9 @@ const IS_WIN_11 = parseInt(version().split('.')[2] || '0', 10) >= 22000;
 export const DEFAULT_STATS = {
 	totalValue: true,
 	valuePerSec: true,
+	activeDps: false,
+	activeTimeMs: false,
+	rollingValuePerSec: false,
 	valuePct: true,
 	critRate: true,
 	critValueRate: true,
@@ -22,8 +25,11 @@ const DEFAULT_SETTINGS = {
 		showYourAbilityScore: true,
 		showOthersAbilityScore: true,
 		resetElapsed: 60,
+		rollingWindow: '15s', // ["5s", "15s", "30s"]
 		shortenAbilityScore: false,
 		bossOnly: false,
+		groupSummonSkills: false,
+		aggroAlert: true,
 		autostart: true
 	},
 	accessibility: {
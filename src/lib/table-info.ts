@@ -65,6 +65,36 @@ export const dpsPlayersColumnDefs = [
 		}
 	}),
 
+	dpsPlayersColumnHelper.accessor('activeDps', {
+		header: 'ADPS',
+		cell: ({ cell }) => renderComponent(AbbreviatedNumber, { num: cell.getValue() }),
+		meta: {
+			class: 'w-12',
+			label: 'ADPS',
+			description: "Show player's damage per second of active time (long gaps between hits excluded)"
+		}
+	}),
+
+	dpsPlayersColumnHelper.accessor('activeTimeMs', {
+		header: 'Active',
+		cell: ({ cell }) => `${(cell.getValue() / 1000).toFixed(0)}s`,
+		meta: {
+			class: 'w-12',
+			label: 'Active',
+			description: "Show player's active time in seconds"
+		}
+	}),
+
+	dpsPlayersColumnHelper.accessor('rollingValuePerSec', {
+		header: 'rDPS',
+		cell: ({ cell }) => renderComponent(AbbreviatedNumber, { num: cell.getValue() }),
+		meta: {
+			class: 'w-12',
+			label: 'rDPS',
+			description: "Show player's damage per second over the last few seconds (see Current DPS Window)"
+		}
+	}),
+
 	dpsPlayersColumnHelper.accessor('valuePct', {
 		header: () => renderComponent(PercentFormat, { val: 'D' }),
 		cell: ({ cell }) => renderComponent(PercentFormat, { val: cell.getValue(), fractionDigits: 0 }),
@@ -142,7 +172,8 @@ export const dpsSkillsColumnDefs = [
 		cell: ({ row }) =>
 			renderComponent(SkillInfo, {
 				skillUid: row.original.uid,
-				skillName: row.original.name
+				skillName: row.original.name,
+				isSummon: row.original.isSummon
 			}),
 		meta: {
 			class: 'w-full',
@@ -171,6 +202,26 @@ export const dpsSkillsColumnDefs = [
 		}
 	}),
 
+	dpsSkillsColumnHelper.accessor('activeDps', {
+		header: 'ADPS',
+		cell: ({ cell }) => renderComponent(AbbreviatedNumber, { num: cell.getValue() }),
+		meta: {
+			class: 'w-12',
+			label: 'ADPS',
+			description: "Show skill's damage per second of active time (long gaps between hits excluded)"
+		}
+	}),
+
+	dpsSkillsColumnHelper.accessor('activeTimeMs', {
+		header: 'Active',
+		cell: ({ cell }) => `${(cell.getValue() / 1000).toFixed(0)}s`,
+		meta: {
+			class: 'w-12',
+			label: 'Active',
+			description: "Show skill's active time in seconds"
+		}
+	}),
+
 	dpsSkillsColumnHelper.accessor('valuePct', {
 		header: () => renderComponent(PercentFormat, { val: 'D' }),
 		cell: ({ cell }) => renderComponent(PercentFormat, { val: cell.getValue(), fractionDigits: 0 }),
@@ -281,6 +332,36 @@ export const healPlayersColumnDefs = [
 		}
 	}),
 
+	healPlayersColumnHelper.accessor('activeDps', {
+		header: 'AHPS',
+		cell: ({ cell }) => renderComponent(AbbreviatedNumber, { num: cell.getValue() }),
+		meta: {
+			class: 'w-12',
+			label: 'AHPS',
+			description: "Show player's heal per second of active time (long gaps between hits excluded)"
+		}
+	}),
+
+	healPlayersColumnHelper.accessor('activeTimeMs', {
+		header: 'Active',
+		cell: ({ cell }) => `${(cell.getValue() / 1000).toFixed(0)}s`,
+		meta: {
+			class: 'w-12',
+			label: 'Active',
+			description: "Show player's active time in seconds"
+		}
+	}),
+
+	healPlayersColumnHelper.accessor('rollingValuePerSec', {
+		header: 'rHPS',
+		cell: ({ cell }) => renderComponent(AbbreviatedNumber, { num: cell.getValue() }),
+		meta: {
+			class: 'w-12',
+			label: 'rHPS',
+			description: "Show player's heal per second over the last few seconds (see Current DPS Window)"
+		}
+	}),
+
 	healPlayersColumnHelper.accessor('valuePct', {
 		header: () => renderComponent(PercentFormat, { val: 'H' }),
 		cell: ({ cell }) => renderComponent(PercentFormat, { val: cell.getValue(), fractionDigits: 0 }),
@@ -358,7 +439,8 @@ export const healSkillsColumnDefs = [
 		cell: ({ row }) =>
 			renderComponent(SkillInfo, {
 				skillUid: row.original.uid,
-				skillName: row.original.name
+				skillName: row.original.name,
+				isSummon: row.original.isSummon
 			}),
 		meta: {
 			class: 'w-full',
@@ -387,6 +469,26 @@ export const healSkillsColumnDefs = [
 		}
 	}),
 
+	healSkillsColumnHelper.accessor('activeDps', {
+		header: 'AHPS',
+		cell: ({ cell }) => renderComponent(AbbreviatedNumber, { num: cell.getValue() }),
+		meta: {
+			class: 'w-12',
+			label: 'AHPS',
+			description: "Show skill's heal per second of active time (long gaps between hits excluded)"
+		}
+	}),
+
+	healSkillsColumnHelper.accessor('activeTimeMs', {
+		header: 'Active',
+		cell: ({ cell }) => `${(cell.getValue() / 1000).toFixed(0)}s`,
+		meta: {
+			class: 'w-12',
+			label: 'Active',
+			description: "Show skill's active time in seconds"
+		}
+	}),
+
 	healSkillsColumnHelper.accessor('valuePct', {
 		header: () => renderComponent(PercentFormat, { val: 'H' }),
 		cell: ({ cell }) => renderComponent(PercentFormat, { val: cell.getValue(), fractionDigits: 0 }),